@@ -1,18 +1,22 @@
 advent_of_code::solution!(4);
 
 use regex::Regex;
-use std::collections::BTreeSet;
-use std::ops::RangeInclusive;
 
+use advent_of_code::majcn::interval::Interval;
 use advent_of_code::majcn::parse::ParseRegex;
 
-fn parse_data(input: &str) -> Vec<(RangeInclusive<u32>, RangeInclusive<u32>)> {
+fn parse_data(input: &str) -> Vec<(Interval, Interval)> {
     let re = Regex::new(r"^(\d+)-(\d+),(\d+)-(\d+)$").unwrap();
 
     input
         .lines()
         .map(|x| re.parse_u32(x))
-        .map(|[r1_min, r1_max, r2_min, r2_max]| (r1_min..=r1_max, r2_min..=r2_max))
+        .map(|[r1_min, r1_max, r2_min, r2_max]| {
+            (
+                Interval::from(r1_min..=r1_max),
+                Interval::from(r2_min..=r2_max),
+            )
+        })
         .collect()
 }
 
@@ -21,14 +25,7 @@ pub fn part_one(input: &str) -> Option<u32> {
 
     let result = data
         .into_iter()
-        .map(|(r1, r2)| (r1.collect::<BTreeSet<u32>>(), r2.collect::<BTreeSet<u32>>()))
-        .filter(|(s1, s2)| {
-            if s1.len() > s2.len() {
-                s2.is_subset(s1)
-            } else {
-                s1.is_subset(s2)
-            }
-        })
+        .filter(|(r1, r2)| r1.contains_interval(r2) || r2.contains_interval(r1))
         .count() as u32;
 
     Some(result)
@@ -37,11 +34,7 @@ pub fn part_one(input: &str) -> Option<u32> {
 pub fn part_two(input: &str) -> Option<u32> {
     let data = parse_data(input);
 
-    let result = data
-        .into_iter()
-        .map(|(r1, r2)| (r1.collect::<BTreeSet<u32>>(), r2.collect::<BTreeSet<u32>>()))
-        .filter(|(s1, s2)| !s1.is_disjoint(s2))
-        .count() as u32;
+    let result = data.into_iter().filter(|(r1, r2)| r1.overlaps(r2)).count() as u32;
 
     Some(result)
 }
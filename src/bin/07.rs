@@ -1,62 +1,53 @@
 advent_of_code::solution!(7);
 
-use advent_of_code::majcn::grid::ArenaTree;
+use advent_of_code::majcn::grid::PathTree;
 
 enum NodeValueEnum {
     File(u32),
     Folder,
 }
 
-fn parse_data(input: &str) -> ArenaTree<NodeValueEnum> {
-    let mut grid = ArenaTree::new(NodeValueEnum::Folder);
-    let mut grid_current_idx = 0;
+fn parse_data(input: &str) -> PathTree<NodeValueEnum> {
+    let mut tree = PathTree::new(NodeValueEnum::Folder);
+    let mut current_idx = tree.root();
 
     for line in input.lines() {
-        if line.starts_with("$ cd") {
-            grid_current_idx = match line.as_bytes()[5] {
-                b'/' => grid_current_idx,
-                b'.' => grid.get_parent(grid_current_idx).unwrap(),
-                _ => grid.insert(grid_current_idx, NodeValueEnum::Folder),
+        if let Some(path) = line.strip_prefix("$ cd ") {
+            current_idx = match tree.resolve(current_idx, path) {
+                Some(idx) => idx,
+                None => tree.get_or_insert_with(current_idx, path, || NodeValueEnum::Folder),
             }
+        } else if let Some(name) = line.strip_prefix("dir ") {
+            tree.get_or_insert_with(current_idx, name, || NodeValueEnum::Folder);
         } else if line.as_bytes()[0].is_ascii_digit() {
-            let size = line
-                .split_once(' ')
-                .map(|(size, _)| size.parse::<u32>().unwrap())
-                .unwrap();
+            let (size, name) = line.split_once(' ').unwrap();
+            let size = size.parse::<u32>().unwrap();
 
-            grid.insert(grid_current_idx, NodeValueEnum::File(size));
+            tree.get_or_insert_with(current_idx, name, || NodeValueEnum::File(size));
         }
     }
 
-    grid
+    tree
 }
 
-fn get_all_folders(grid: &ArenaTree<NodeValueEnum>) -> Vec<usize> {
-    grid.iter()
-        .enumerate()
-        .filter_map(|(i, v)| match v {
-            NodeValueEnum::Folder => Some(i),
-            _ => None,
-        })
-        .collect()
-}
+fn calculate_folder_sizes(tree: &PathTree<NodeValueEnum>) -> Vec<u32> {
+    let grid = tree.tree();
 
-fn calculate_size(grid: &ArenaTree<NodeValueEnum>, node: usize) -> u32 {
-    match grid[node] {
+    let sizes = grid.fold(grid.root(), |node, children| match node.value {
         NodeValueEnum::File(v) => v,
-        NodeValueEnum::Folder => grid
-            .get_children(node)
-            .into_iter()
-            .map(|node| calculate_size(grid, node))
-            .sum(),
-    }
+        NodeValueEnum::Folder => children.sum(),
+    });
+
+    grid.iter_with_keys()
+        .filter(|(_, node)| matches!(node.value, NodeValueEnum::Folder))
+        .filter_map(|(i, _)| sizes[i])
+        .collect()
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
     let data = parse_data(input);
-    let result = get_all_folders(&data)
+    let result = calculate_folder_sizes(&data)
         .into_iter()
-        .map(|folder| calculate_size(&data, folder))
         .filter(|size| size < &100000)
         .sum();
 
@@ -69,10 +60,10 @@ pub fn part_two(input: &str) -> Option<u32> {
     const TOTAL_DISK_SPACE: u32 = 70_000_000;
     const REQUIRED_DISK_SPACE: u32 = 30_000_000;
 
-    let used_disk_space = calculate_size(&data, 0);
-    let result = get_all_folders(&data)
+    let folder_sizes = calculate_folder_sizes(&data);
+    let used_disk_space = folder_sizes[0];
+    let result = folder_sizes
         .into_iter()
-        .map(|folder| calculate_size(&data, folder))
         .filter(|size| used_disk_space - size < TOTAL_DISK_SPACE - REQUIRED_DISK_SPACE)
         .min()
         .unwrap();
@@ -1,117 +1,50 @@
 advent_of_code::solution!(10);
 
-mod interpreter {
-    enum Command {
-        Noop,
-        AddX(i32),
-    }
-
-    pub struct Interpreter<'a> {
-        program: Vec<&'a str>,
-        program_index: usize,
-        current_program_progress: u8,
-        current_command: Command,
-        pub register_x: i32,
-        pub cycle: usize,
-        pub halt: bool,
-    }
-
-    impl<'a> Interpreter<'a> {
-        pub fn new(program: Vec<&'a str>) -> Self {
-            let mut interpreter = Interpreter {
-                program,
-                program_index: 0,
-                current_program_progress: 0,
-                current_command: Command::Noop,
-                register_x: 1,
-                cycle: 0,
-                halt: false,
-            };
-
-            interpreter.init_next_command();
-
-            interpreter
-        }
-
-        fn init_next_command(&mut self) {
-            let command = self.program[self.program_index];
-            let (command_name, command_value) = command.split_once(' ').unwrap_or((command, ""));
-
-            (self.current_program_progress, self.current_command) = match command_name {
-                "noop" => (1, Command::Noop),
-                "addx" => (2, Command::AddX(command_value.parse().unwrap())),
-                _ => unreachable!(),
-            };
-
-            self.program_index += 1;
-        }
-
-        fn execute_command(&mut self) {
-            self.register_x += match self.current_command {
-                Command::Noop => 0,
-                Command::AddX(v) => v,
-            }
-        }
+use advent_of_code::majcn::vm::{Instruction, InstructionSet, Vm};
 
-        pub fn exec_single_cycle(&mut self) {
-            if self.halt {
-                return;
-            }
-
-            self.cycle += 1;
-
-            if self.current_program_progress == 1 {
-                self.execute_command();
-
-                if self.program_index == self.program.len() {
-                    self.halt = true;
-                    return;
-                }
-
-                self.init_next_command();
-            } else {
-                self.current_program_progress -= 1;
-            }
-        }
-    }
+fn instruction_set() -> InstructionSet {
+    InstructionSet::new(&["x"])
+        .instruction("noop", 0, 1, |_, _| {})
+        .instruction("addx", 1, 2, |s, o| s.registers[0] += s.get(&o[0]))
 }
 
-fn parse_data(input: &str) -> Vec<&str> {
-    input.lines().collect()
+fn parse_data(set: &InstructionSet, input: &str) -> Vec<Instruction> {
+    set.parse(input).unwrap()
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let data = parse_data(input);
+pub fn part_one(input: &str) -> Option<i64> {
+    let set = instruction_set();
+    let data = parse_data(&set, input);
 
     let mut result = 0;
 
-    let mut p = interpreter::Interpreter::new(data);
-    while !p.halt {
-        result += match p.cycle + 1 {
-            20 | 60 | 100 | 140 | 180 | 220 => (p.cycle as u32 + 1) * (p.register_x as u32),
+    let mut vm = Vm::new(&set, data).with_register("x", 1);
+    vm.run(|cycle, state| {
+        result += match cycle {
+            20 | 60 | 100 | 140 | 180 | 220 => cycle as i64 * state.registers[0],
             _ => 0,
         };
-
-        p.exec_single_cycle();
-    }
+    });
 
     Some(result)
 }
 
 pub fn part_two(input: &str) -> Option<String> {
-    let data = parse_data(input);
+    let set = instruction_set();
+    let data = parse_data(&set, input);
 
     let mut display = [[false; 40]; 6];
 
-    let mut p = interpreter::Interpreter::new(data);
-    while !p.halt {
-        let x = p.cycle % 40;
-        let y = p.cycle / 40;
-
-        display[y][x] = matches!(p.register_x - x as i32, -1..=1);
+    let mut vm = Vm::new(&set, data).with_register("x", 1);
+    vm.run(|cycle, state| {
+        let x = (cycle as usize - 1) % 40;
+        let y = (cycle as usize - 1) / 40;
 
-        p.exec_single_cycle();
-    }
+        // the screen ends after 240 cycles
+        if let Some(pixel) = display.get_mut(y).map(|line| &mut line[x]) {
+            *pixel = matches!(state.registers[0] - x as i64, -1..=1);
+        }
+    });
 
     let mut result = String::with_capacity(display.len() * (display[0].len() + 1));
     for line in display {
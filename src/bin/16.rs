@@ -2,12 +2,17 @@ advent_of_code::solution!(16);
 
 use regex::Regex;
 use std::collections::HashMap;
-use std::collections::HashSet;
 use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+
+use advent_of_code::majcn::branch_bound::BranchAndBound;
+use advent_of_code::majcn::branch_bound::Problem;
 
 type ValveName = [char; 2];
 type ValveArray = HashMap<ValveName, Valve>;
 
+const START: ValveName = ['A', 'A'];
+
 struct Valve {
     name: ValveName,
     flow_rate: u32,
@@ -35,145 +40,294 @@ fn parse_data(input: &str) -> ValveArray {
         .collect()
 }
 
-fn bfs(data: &ValveArray, start_node_name: ValveName, end_node_name: ValveName) -> Option<u32> {
+fn bfs(data: &ValveArray, start_node_name: ValveName) -> HashMap<ValveName, u32> {
     let mut queue = VecDeque::new();
-    let mut visited = HashSet::new();
+    let mut visited = HashMap::new();
 
-    queue.push_back((start_node_name, 0));
-    visited.insert(start_node_name);
+    queue.push_back(start_node_name);
+    visited.insert(start_node_name, 0);
 
-    while let Some((node_name, time)) = queue.pop_front() {
-        if node_name == end_node_name {
-            return Some(time);
-        }
+    while let Some(node_name) = queue.pop_front() {
+        let time = visited[&node_name];
 
         for neighbor_name in data[&node_name].tunnels.iter() {
-            if !visited.contains(neighbor_name) {
-                queue.push_back((*neighbor_name, time + 1));
-                visited.insert(*neighbor_name);
+            if !visited.contains_key(neighbor_name) {
+                queue.push_back(*neighbor_name);
+                visited.insert(*neighbor_name, time + 1);
             }
         }
     }
 
-    None
+    visited
 }
 
+#[derive(Clone, PartialEq, Eq, Hash)]
 struct State {
     time: u32,
     location: ValveName,
     pressure_released: u32,
-    opened_valves: HashSet<ValveName>,
+    // bit i is set when useful_valves[i] is opened
+    opened_valves: u64,
+    // indices of opened useful valves in the order of opening
+    route: Vec<usize>,
 }
 
-fn find_all_final_states<F>(init_state: State, next_states_f: F) -> Vec<State>
-where
-    F: Fn(&State) -> Vec<State>,
-{
-    let mut candidates: Vec<State> = vec![];
+struct Tunnels {
+    useful_valves: Vec<(ValveName, u32)>,
+    // time to walk to a useful valve and open it
+    valve_paths: HashMap<ValveName, Vec<u32>>,
+    max_time: u32,
+}
 
-    // TODO: dejansko ne znam napisat rekurzije za to v rustu...
-    let mut queue = vec![init_state];
-    while let Some(state) = queue.pop() {
-        queue.extend(next_states_f(&state));
-        candidates.push(state);
+impl Tunnels {
+    fn new(data: &ValveArray, max_time: u32) -> Self {
+        let mut useful_valves = data
+            .values()
+            .filter(|v| v.flow_rate > 0)
+            .map(|v| (v.name, v.flow_rate))
+            .collect::<Vec<_>>();
+        useful_valves.sort_unstable();
+        assert!(useful_valves.len() < 64, "too many useful valves!");
+
+        let valve_paths = data
+            .keys()
+            .map(|&name| {
+                let distances = bfs(data, name);
+                let paths = useful_valves.iter().map(|v| distances[&v.0] + 1);
+                (name, paths.collect())
+            })
+            .collect();
+
+        Tunnels {
+            useful_valves,
+            valve_paths,
+            max_time,
+        }
     }
 
-    candidates
-}
-
-fn get_next_states(
-    data: &ValveArray,
-    state: &State,
-    valve_paths: &HashMap<ValveName, Vec<(ValveName, u32)>>,
-    max_time: u32,
-) -> Vec<State> {
-    let mut result = Vec::with_capacity(valve_paths[&state.location].len());
+    // useful valves which can still be opened in time, with the time of opening
+    fn reachable<'a>(&'a self, state: &'a State) -> impl Iterator<Item = (usize, u32)> + 'a {
+        self.valve_paths[&state.location]
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| state.opened_valves & (1 << i) == 0)
+            .map(|(i, &path)| (i, state.time + path))
+            .filter(|&(_, new_time)| new_time <= self.max_time)
+    }
 
-    for valve_path in &valve_paths[&state.location] {
-        if state.opened_valves.contains(&valve_path.0) {
-            continue;
+    fn init_state(&self) -> State {
+        State {
+            time: 0,
+            location: START,
+            pressure_released: 0,
+            opened_valves: 0,
+            route: vec![],
         }
+    }
 
-        let new_time = state.time + valve_path.1;
-        if new_time > max_time {
-            continue;
+    fn all_valves(&self) -> usize {
+        (1 << self.useful_valves.len()) - 1
+    }
+
+    // best pressure of a single agent which opens only valves from the mask
+    fn best_per_mask(&self) -> Vec<u32> {
+        assert!(self.useful_valves.len() <= 20, "too many useful valves!");
+
+        let mut result = vec![0; self.all_valves() + 1];
+        BranchAndBound::new(self)
+            .without_memo()
+            .explore(self.init_state(), |state| {
+                let best = &mut result[state.opened_valves as usize];
+                *best = (*best).max(state.pressure_released);
+            });
+
+        // subset max propagation, every mask also gets the best of its submasks
+        for i in 0..self.useful_valves.len() {
+            for mask in 0..result.len() {
+                if mask & (1 << i) != 0 {
+                    result[mask] = result[mask].max(result[mask ^ (1 << i)]);
+                }
+            }
         }
 
-        let new_pressure_released =
-            state.pressure_released + (max_time - new_time) * data[&valve_path.0].flow_rate;
+        result
+    }
+
+    // best single agent which opens only valves from the mask, the rest counts as opened
+    fn best_route(&self, mask: usize) -> State {
+        let mut init_state = self.init_state();
+        init_state.opened_valves = (self.all_valves() & !mask) as u64;
+
+        BranchAndBound::new(self).solve(init_state).state
+    }
+}
+
+impl Problem for Tunnels {
+    type State = State;
+    type Key = (ValveName, u64);
+    type Value = u32;
+
+    fn successors(&self, state: &State) -> Vec<State> {
+        self.reachable(state)
+            .map(|(i, new_time)| {
+                let (name, flow_rate) = self.useful_valves[i];
+                let mut route = state.route.clone();
+                route.push(i);
+
+                State {
+                    time: new_time,
+                    location: name,
+                    pressure_released: state.pressure_released
+                        + (self.max_time - new_time) * flow_rate,
+                    opened_valves: state.opened_valves | (1 << i),
+                    route,
+                }
+            })
+            .collect()
+    }
+
+    fn objective(&self, state: &State) -> u32 {
+        state.pressure_released
+    }
 
-        let mut new_opened_valves = HashSet::with_capacity(state.opened_valves.len() + 1);
-        new_opened_valves.extend(state.opened_valves.iter());
-        new_opened_valves.insert(valve_path.0);
+    // as if every valve could be reached directly from here
+    fn bound(&self, state: &State) -> u32 {
+        let additional = self
+            .reachable(state)
+            .map(|(i, new_time)| (self.max_time - new_time) * self.useful_valves[i].1)
+            .sum::<u32>();
 
-        result.push(State {
-            time: state.time + valve_path.1,
-            location: valve_path.0,
-            pressure_released: new_pressure_released,
-            opened_valves: new_opened_valves,
-        });
+        state.pressure_released + additional
     }
 
-    result
+    fn dominance_key(&self, state: &State) -> Option<Self::Key> {
+        Some((state.location, state.opened_valves))
+    }
+
+    fn dominates(&self, a: &State, b: &State) -> bool {
+        a.time <= b.time && a.pressure_released >= b.pressure_released
+    }
 }
 
-fn part_x<const T: u32>(data: &ValveArray) -> Vec<State> {
-    let mut valve_paths: HashMap<ValveName, Vec<(ValveName, u32)>> = HashMap::new();
+struct Plan {
+    pressure_released: u32,
+    // opened valves and the minute of opening, per agent
+    routes: Vec<Vec<(ValveName, u32)>>,
+}
 
-    for (i, v1) in data.values().enumerate() {
-        for (j, v2) in data.values().enumerate() {
-            if i != j && v2.flow_rate > 0 {
-                let p = valve_paths.entry(v1.name).or_default();
-                p.push((v2.name, bfs(data, v1.name, v2.name).unwrap() + 1));
+impl Display for Plan {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, route) in self.routes.iter().enumerate() {
+            write!(f, "agent {}:", i + 1)?;
+            for (name, time) in route {
+                write!(f, " {}{}@{}", name[0], name[1], time)?;
             }
+            writeln!(f)?;
         }
+        write!(f, "pressure released: {}", self.pressure_released)
     }
+}
 
-    let init_state = State {
-        time: 0,
-        location: ['A', 'A'],
-        pressure_released: 0,
-        opened_valves: HashSet::new(),
-    };
+// one mask of valves per agent, together they release the most pressure
+fn split(tunnels: &Tunnels, agents: usize) -> Vec<usize> {
+    let full = tunnels.all_valves();
+    if agents == 1 {
+        return vec![full];
+    }
 
-    find_all_final_states(init_state, |state| {
-        get_next_states(data, state, &valve_paths, T)
-    })
+    // every submask of every mask is visited with three or more agents
+    assert!(
+        agents == 2 || tunnels.useful_valves.len() <= 16,
+        "too many useful valves to split between more than two agents!"
+    );
+
+    let best = tunnels.best_per_mask();
+
+    // with[k][mask] is the best for k + 1 agents sharing the valves in mask
+    let mut with = vec![best.clone()];
+    for k in 1..agents {
+        let masks = if k + 1 == agents {
+            full..=full
+        } else {
+            0..=full
+        };
+
+        let mut current = vec![0; best.len()];
+        for mask in masks {
+            let mut sub = mask;
+            loop {
+                current[mask] = current[mask].max(best[sub] + with[k - 1][mask ^ sub]);
+                if sub == 0 {
+                    break;
+                }
+                sub = (sub - 1) & mask;
+            }
+        }
+        with.push(current);
+    }
+
+    // walk back through the choices to find the valves of every agent
+    let mut masks = vec![];
+    let mut mask = full;
+    for k in (1..agents).rev() {
+        let mut sub = mask;
+        while best[sub] + with[k - 1][mask ^ sub] != with[k][mask] {
+            sub = (sub - 1) & mask;
+        }
+        masks.push(sub);
+        mask ^= sub;
+    }
+    masks.push(mask);
+
+    masks
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let data = parse_data(input);
+// agents start together and open disjoint sets of valves
+fn plan(data: &ValveArray, agents: usize, max_time: u32) -> Plan {
+    assert!(agents > 0, "no agents!");
 
-    let mut all_final_states = part_x::<30>(&data);
-    all_final_states.sort_unstable_by_key(|x| x.pressure_released);
+    let tunnels = Tunnels::new(data, max_time);
+    let states = split(&tunnels, agents)
+        .into_iter()
+        .map(|mask| tunnels.best_route(mask))
+        .collect::<Vec<_>>();
 
-    let result = all_final_states
+    let routes = states
         .iter()
-        .rev()
-        .map(|x| x.pressure_released)
-        .next();
+        .map(|state| {
+            let mut time = 0;
+            let mut location = START;
+            state
+                .route
+                .iter()
+                .map(|&i| {
+                    let name = tunnels.useful_valves[i].0;
+                    time += tunnels.valve_paths[&location][i];
+                    location = name;
+                    (name, time)
+                })
+                .collect()
+        })
+        .collect();
 
-    result
+    Plan {
+        pressure_released: states.iter().map(|x| x.pressure_released).sum(),
+        routes,
+    }
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
+pub fn part_one(input: &str) -> Option<u32> {
     let data = parse_data(input);
 
-    let mut all_final_states = part_x::<26>(&data);
-    all_final_states.sort_unstable_by_key(|x| x.pressure_released);
+    let result = plan(&data, 1, 30).pressure_released;
 
-    let mut result = 0;
-    for s1 in all_final_states.iter().rev() {
-        for s2 in all_final_states.iter().rev() {
-            if result >= s1.pressure_released + s2.pressure_released {
-                break;
-            }
+    Some(result)
+}
 
-            if s1.opened_valves.is_disjoint(&s2.opened_valves) {
-                result = u32::max(result, s1.pressure_released + s2.pressure_released);
-            }
-        }
-    }
+pub fn part_two(input: &str) -> Option<u32> {
+    let data = parse_data(input);
+
+    let result = plan(&data, 2, 26).pressure_released;
 
     Some(result)
 }
@@ -193,4 +347,50 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(1707));
     }
+
+    // routes are disjoint and their opening times add up to the released pressure
+    fn check(data: &ValveArray, plan: &Plan, max_time: u32) {
+        let mut opened = std::collections::HashSet::new();
+        let mut pressure = 0;
+        for route in plan.routes.iter() {
+            for (name, time) in route {
+                assert!(opened.insert(*name), "valve opened twice");
+                assert!(*time <= max_time);
+                pressure += (max_time - time) * data[name].flow_rate;
+            }
+        }
+        assert_eq!(pressure, plan.pressure_released);
+    }
+
+    #[test]
+    fn test_plan() {
+        let data = parse_data(&advent_of_code::template::read_file("examples", DAY));
+
+        let result = plan(&data, 2, 26);
+        check(&data, &result, 26);
+        assert_eq!(
+            result.to_string(),
+            "agent 1: JJ@3 BB@7 CC@9\nagent 2: DD@2 HH@7 EE@11\npressure released: 1707"
+        );
+
+        let result = plan(&data, 3, 26);
+        check(&data, &result, 26);
+        assert_eq!(result.routes.len(), 3);
+        assert_eq!(result.pressure_released, 1794);
+
+        let result = plan(&data, 4, 26);
+        check(&data, &result, 26);
+        assert_eq!(result.pressure_released, 1825);
+
+        let result = plan(&data, 1, 20);
+        check(&data, &result, 20);
+        assert_eq!(
+            result.to_string(),
+            "agent 1: DD@2 BB@5 JJ@9 HH@17\npressure released: 852"
+        );
+
+        let result = plan(&data, 3, 5);
+        check(&data, &result, 5);
+        assert_eq!(result.pressure_released, 146);
+    }
 }
@@ -1,22 +1,26 @@
 advent_of_code::solution!(19);
 
 use regex::Regex;
-use std::collections::HashSet;
-use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
 
+use advent_of_code::majcn::branch_bound::BranchAndBound;
+use advent_of_code::majcn::branch_bound::Problem;
+use advent_of_code::majcn::branch_bound::Stats;
 use advent_of_code::majcn::parse::ParseRegex;
 
+const ORE: usize = 0;
+const CLAY: usize = 1;
+const OBSIDIAN: usize = 2;
+const GEODE: usize = 3;
+
+const ROBOT_NAMES: [&str; 4] = ["ore", "clay", "obsidian", "geode"];
+
 struct Blueprint {
     id: u32,
-    ore_robot_cost_ore: u32,
-    clay_robot_cost_ore: u32,
-    obsidian_robot_cost_ore: u32,
-    obsidian_robot_cost_clay: u32,
-    geode_robot_cost_ore: u32,
-    geode_robot_cost_obsidian: u32,
-    max_ore_cost: u32,
-    max_clay_cost: u32,
-    max_obsidian_cost: u32,
+    // costs[robot][resource]
+    costs: [[u32; 4]; 4],
+    // there is no point in producing more than we can spend in a minute
+    max_robots: [u32; 4],
 }
 
 fn parse_data(input: &str) -> Vec<Blueprint> {
@@ -33,222 +37,198 @@ fn parse_data(input: &str) -> Vec<Blueprint> {
         .lines()
         .map(|x| re.parse_u32::<7>(x))
         .map(|[id, ore_robot_cost_ore, clay_robot_cost_ore, obsidian_robot_cost_ore, obsidian_robot_cost_clay, geode_robot_cost_ore, geode_robot_cost_obsidian]| {
-            let max_obsidian_cost = geode_robot_cost_obsidian;
-            let max_clay_cost = obsidian_robot_cost_clay;
-            let max_ore_cost = [ore_robot_cost_ore, clay_robot_cost_ore, obsidian_robot_cost_ore, geode_robot_cost_ore].into_iter().max().unwrap();
+            let costs = [
+                [ore_robot_cost_ore, 0, 0, 0],
+                [clay_robot_cost_ore, 0, 0, 0],
+                [obsidian_robot_cost_ore, obsidian_robot_cost_clay, 0, 0],
+                [geode_robot_cost_ore, 0, geode_robot_cost_obsidian, 0],
+            ];
+
+            let mut max_robots = [u32::MAX; 4];
+            for resource in [ORE, CLAY, OBSIDIAN] {
+                max_robots[resource] = costs.iter().map(|x| x[resource]).max().unwrap();
+            }
 
             Blueprint {
                 id,
-                ore_robot_cost_ore,
-                clay_robot_cost_ore,
-                obsidian_robot_cost_ore,
-                obsidian_robot_cost_clay,
-                geode_robot_cost_ore,
-                geode_robot_cost_obsidian,
-                max_ore_cost,
-                max_clay_cost,
-                max_obsidian_cost,
+                costs,
+                max_robots,
             }
         })
         .collect()
 }
 
-#[derive(Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Clone, PartialEq, Eq, Hash)]
 struct State {
     time: u32,
-    ore: u32,
-    clay: u32,
-    obsidian: u32,
-    geode: u32,
-    ore_robots: u32,
-    clay_robots: u32,
-    obsidian_robots: u32,
-    geode_robots: u32,
+    resources: [u32; 4],
+    robots: [u32; 4],
+    // kind of every built robot, in the order they were built
+    build_order: Vec<usize>,
 }
 
-fn get_next_states(blueprint: &Blueprint, state: &State) -> Vec<State> {
-    if state.ore >= blueprint.geode_robot_cost_ore
-        && state.obsidian >= blueprint.geode_robot_cost_obsidian
-    {
-        return vec![State {
-            time: state.time + 1,
-            ore: state.ore + state.ore_robots - blueprint.geode_robot_cost_ore,
-            clay: state.clay + state.clay_robots,
-            obsidian: state.obsidian + state.obsidian_robots - blueprint.geode_robot_cost_obsidian,
-            geode: state.geode + state.geode_robots,
-            ore_robots: state.ore_robots,
-            clay_robots: state.clay_robots,
-            obsidian_robots: state.obsidian_robots,
-            geode_robots: state.geode_robots + 1,
-        }];
-    }
+struct Factory<'a> {
+    blueprint: &'a Blueprint,
+    max_time: u32,
+}
 
-    if state.obsidian_robots < blueprint.max_obsidian_cost
-        && state.ore >= blueprint.obsidian_robot_cost_ore
-        && state.clay >= blueprint.obsidian_robot_cost_clay
-    {
-        return vec![State {
-            time: state.time + 1,
-            ore: state.ore + state.ore_robots - blueprint.obsidian_robot_cost_ore,
-            clay: state.clay + state.clay_robots - blueprint.obsidian_robot_cost_clay,
-            obsidian: state.obsidian + state.obsidian_robots,
-            geode: state.geode + state.geode_robots,
-            ore_robots: state.ore_robots,
-            clay_robots: state.clay_robots,
-            obsidian_robots: state.obsidian_robots + 1,
-            geode_robots: state.geode_robots,
-        }];
-    }
+impl Factory<'_> {
+    // minutes of waiting until robot is affordable, None if it never is
+    fn wait_time(&self, state: &State, robot: usize) -> Option<u32> {
+        let mut result = 0;
+        for (resource, &cost) in self.blueprint.costs[robot].iter().enumerate() {
+            let missing = cost.saturating_sub(state.resources[resource]);
+            if missing > 0 {
+                let production = state.robots[resource];
+                if production == 0 {
+                    return None;
+                }
+                result = result.max(missing.div_ceil(production));
+            }
+        }
 
-    let mut result = Vec::with_capacity(3);
-
-    if state.clay_robots < blueprint.max_clay_cost && state.ore >= blueprint.clay_robot_cost_ore {
-        result.push(State {
-            time: state.time + 1,
-            ore: state.ore + state.ore_robots - blueprint.clay_robot_cost_ore,
-            clay: state.clay + state.clay_robots,
-            obsidian: state.obsidian + state.obsidian_robots,
-            geode: state.geode + state.geode_robots,
-            ore_robots: state.ore_robots,
-            clay_robots: state.clay_robots + 1,
-            obsidian_robots: state.obsidian_robots,
-            geode_robots: state.geode_robots,
-        });
+        Some(result)
     }
 
-    if state.ore_robots < blueprint.max_ore_cost && state.ore >= blueprint.ore_robot_cost_ore {
-        result.push(State {
-            time: state.time + 1,
-            ore: state.ore + state.ore_robots - blueprint.ore_robot_cost_ore,
-            clay: state.clay + state.clay_robots,
-            obsidian: state.obsidian + state.obsidian_robots,
-            geode: state.geode + state.geode_robots,
-            ore_robots: state.ore_robots + 1,
-            clay_robots: state.clay_robots,
-            obsidian_robots: state.obsidian_robots,
-            geode_robots: state.geode_robots,
-        });
-    }
+    // skips straight to the minute when robot is finished
+    fn build(&self, state: &State, robot: usize) -> Option<State> {
+        if state.robots[robot] >= self.blueprint.max_robots[robot] {
+            return None;
+        }
 
-    result.push(State {
-        time: state.time + 1,
-        ore: state.ore + state.ore_robots,
-        clay: state.clay + state.clay_robots,
-        obsidian: state.obsidian + state.obsidian_robots,
-        geode: state.geode + state.geode_robots,
-        ore_robots: state.ore_robots,
-        clay_robots: state.clay_robots,
-        obsidian_robots: state.obsidian_robots,
-        geode_robots: state.geode_robots,
-    });
-
-    result
-}
+        let elapsed = self.wait_time(state, robot)? + 1;
+        if state.time + elapsed >= self.max_time {
+            return None;
+        }
 
-fn sum_natural_numbers(a: i32, d: i32, n: i32) -> i32 {
-    n * (2 * a + (n - 1) * d) / 2
-}
+        let mut result = state.clone();
+        result.time += elapsed;
+        for resource in 0..4 {
+            result.resources[resource] += state.robots[resource] * elapsed;
+            result.resources[resource] -= self.blueprint.costs[robot][resource];
+        }
+        result.robots[robot] += 1;
+        result.build_order.push(robot);
 
-fn can_be_discarded(
-    state: &State,
-    best_result: &State,
-    blueprint: &Blueprint,
-    max_time: u32,
-) -> bool {
-    let diff_time = max_time as i32 - state.time as i32;
-
-    let calculated_state_clay =
-        state.clay as i32 + sum_natural_numbers(state.clay_robots as i32, 1, diff_time);
-
-    let max_additional_obsidian_robots = i32::min(
-        calculated_state_clay / blueprint.obsidian_robot_cost_clay as i32,
-        diff_time,
-    );
-    let calculated_state_obsidian = state.obsidian as i32
-        + sum_natural_numbers(
-            state.obsidian_robots as i32,
-            1,
-            max_additional_obsidian_robots,
-        )
-        + (diff_time - max_additional_obsidian_robots)
-            * (state.obsidian_robots as i32 + max_additional_obsidian_robots);
-
-    let max_additional_geode_robots = i32::min(
-        calculated_state_obsidian / blueprint.geode_robot_cost_obsidian as i32,
-        diff_time,
-    );
-    let calculated_state_geode = state.geode as i32
-        + sum_natural_numbers(state.geode_robots as i32, 1, max_additional_geode_robots)
-        + (diff_time - max_additional_geode_robots)
-            * (state.geode_robots as i32 + max_additional_geode_robots);
-
-    calculated_state_geode <= best_result.geode as i32
+        Some(result)
+    }
 }
 
-struct Dfs {
-    init_state: State,
-    blueprint: Blueprint,
-    max_time: u32,
-    best_result: State,
-    discovered: HashSet<State>,
-}
+impl Problem for Factory<'_> {
+    type State = State;
+    type Key = [u32; 4];
+    type Value = u32;
 
-impl Dfs {
-    fn find_best(&mut self) -> State {
-        let mut queue = VecDeque::new();
-        queue.push_front(self.init_state);
+    fn successors(&self, state: &State) -> Vec<State> {
+        [GEODE, OBSIDIAN, CLAY, ORE]
+            .into_iter()
+            .filter_map(|robot| self.build(state, robot))
+            .collect()
+    }
 
-        while let Some(current_state) = queue.pop_front() {
-            self.discovered.insert(current_state);
+    // geode robots keep working until the end
+    fn objective(&self, state: &State) -> u32 {
+        state.resources[GEODE] + state.robots[GEODE] * (self.max_time - state.time)
+    }
 
-            for n in get_next_states(&self.blueprint, &current_state)
-                .into_iter()
-                .rev()
-            {
-                if self.discovered.contains(&n) {
-                    continue;
-                }
+    // ore is free and a robot of every other kind can be built each minute
+    fn bound(&self, state: &State) -> u32 {
+        let costs = &self.blueprint.costs;
+        let mut resources = state.resources;
+        let mut robots = state.robots;
 
-                if n.time == self.max_time {
-                    if self.best_result.geode < n.geode {
-                        self.best_result = n;
-                    }
-                    continue;
-                }
+        for _ in state.time..self.max_time {
+            let build_geode = resources[OBSIDIAN] >= costs[GEODE][OBSIDIAN];
+            let build_obsidian = resources[CLAY] >= costs[OBSIDIAN][CLAY];
 
-                if can_be_discarded(&n, &self.best_result, &self.blueprint, self.max_time) {
-                    continue;
-                }
+            for resource in [CLAY, OBSIDIAN, GEODE] {
+                resources[resource] += robots[resource];
+            }
 
-                queue.push_front(n);
+            if build_geode {
+                resources[OBSIDIAN] -= costs[GEODE][OBSIDIAN];
+                robots[GEODE] += 1;
+            }
+            if build_obsidian {
+                resources[CLAY] -= costs[OBSIDIAN][CLAY];
+                robots[OBSIDIAN] += 1;
             }
+            robots[CLAY] += 1;
         }
 
-        self.best_result
+        resources[GEODE]
     }
+
+    fn dominance_key(&self, state: &State) -> Option<Self::Key> {
+        Some(state.robots)
+    }
+
+    fn dominates(&self, a: &State, b: &State) -> bool {
+        a.time <= b.time && (0..4).all(|x| a.resources[x] >= b.resources[x])
+    }
+}
+
+struct BlueprintResult {
+    id: u32,
+    geodes: u32,
+    // finishing minute and kind of every built robot
+    build_order: Vec<(u32, usize)>,
+    stats: Stats,
 }
 
-fn find_max_geodes<const MAX_TIME: u32>(blueprint: Blueprint) -> u32 {
-    let mut dfs = Dfs {
-        init_state: State {
-            ore_robots: 1,
-            ..Default::default()
-        },
+impl Display for BlueprintResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Blueprint {}: {} geodes,", self.id, self.geodes)?;
+        for (time, robot) in self.build_order.iter() {
+            write!(f, " {}@{}", ROBOT_NAMES[*robot], time)?;
+        }
+        write!(f, " ({})", self.stats)
+    }
+}
+
+fn evaluate(blueprint: &Blueprint, max_time: u32) -> BlueprintResult {
+    let factory = Factory {
         blueprint,
-        max_time: MAX_TIME,
-        best_result: State::default(),
-        discovered: HashSet::new(),
+        max_time,
+    };
+
+    let init_state = State {
+        time: 0,
+        resources: [0; 4],
+        robots: [1, 0, 0, 0],
+        build_order: vec![],
     };
 
-    dfs.find_best().geode
+    let solution = BranchAndBound::new(&factory).solve(init_state.clone());
+
+    // replaying the order finds the same minutes as the search
+    let mut state = init_state;
+    let mut build_order = vec![];
+    for &robot in solution.state.build_order.iter() {
+        state = factory.build(&state, robot).unwrap();
+        build_order.push((state.time, robot));
+    }
+
+    BlueprintResult {
+        id: blueprint.id,
+        geodes: solution.value,
+        build_order,
+        stats: solution.stats,
+    }
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let data = parse_data(input);
+// best result of every blueprint, prints as a list of built robots
+fn evaluate_all(input: &str, max_time: u32) -> Vec<BlueprintResult> {
+    parse_data(input)
+        .iter()
+        .map(|b| evaluate(b, max_time))
+        .collect()
+}
 
-    let result = data
-        .into_iter()
-        .map(|b| b.id * find_max_geodes::<24>(b))
+pub fn part_one(input: &str) -> Option<u32> {
+    let result = evaluate_all(input, 24)
+        .iter()
+        .map(|r| r.id * r.geodes)
         .sum();
 
     Some(result)
@@ -258,9 +238,9 @@ pub fn part_two(input: &str) -> Option<u32> {
     let data = parse_data(input);
 
     let result = data
-        .into_iter()
+        .iter()
         .take(3)
-        .map(find_max_geodes::<32>)
+        .map(|b| evaluate(b, 32).geodes)
         .product();
 
     Some(result)
@@ -281,4 +261,47 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(29348));
     }
+
+    #[test]
+    fn test_build_order() {
+        let input = "Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.";
+
+        let results = evaluate_all(input, 24);
+        assert_eq!(results.len(), 1);
+
+        let result = &results[0];
+        assert_eq!(result.geodes, 9);
+        assert_eq!(
+            result.build_order,
+            vec![
+                (3, CLAY),
+                (5, CLAY),
+                (7, CLAY),
+                (11, OBSIDIAN),
+                (12, CLAY),
+                (15, OBSIDIAN),
+                (18, GEODE),
+                (21, GEODE)
+            ]
+        );
+        assert_eq!(
+            result.to_string(),
+            format!(
+                "Blueprint 1: 9 geodes, clay@3 clay@5 clay@7 obsidian@11 clay@12 obsidian@15 geode@18 geode@21 ({})",
+                result.stats
+            )
+        );
+
+        // long runs build more than 32 robots
+        let result = &evaluate_all(input, 44)[0];
+        assert!(result.build_order.len() > 32);
+
+        let geodes = result
+            .build_order
+            .iter()
+            .filter(|(_, robot)| *robot == GEODE)
+            .map(|(time, _)| 44 - time)
+            .sum::<u32>();
+        assert_eq!(result.geodes, geodes);
+    }
 }
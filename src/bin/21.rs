@@ -1,23 +1,10 @@
 advent_of_code::solution!(21);
 
+use advent_of_code::majcn::expr::{Definition, Equation, Expr, Op};
 use regex::Regex;
 use std::collections::HashMap;
 
-type NodeId = [u8; 4];
-
-enum Operator {
-    Mul,
-    Div,
-    Add,
-    Sub,
-}
-
-enum Node {
-    ValueNode(u64),
-    EquationNode(NodeId, NodeId, Operator),
-}
-
-fn parse_data(input: &str) -> HashMap<NodeId, Node> {
+fn parse_data(input: &str) -> HashMap<&str, Definition<&str>> {
     let re_str_equation: &str = r"^(\w{4}): (\w{4}) ([+\-*/]) (\w{4})$";
     let re_equation = Regex::new(re_str_equation).unwrap();
 
@@ -27,106 +14,43 @@ fn parse_data(input: &str) -> HashMap<NodeId, Node> {
     input
         .lines()
         .map(|x| {
-            if x.len() == 17 {
-                let captures = re_equation.captures(x).unwrap();
-
-                let left = captures[2].as_bytes().try_into().unwrap();
-                let right = captures[4].as_bytes().try_into().unwrap();
-                let operator = match &captures[3] {
-                    "+" => Operator::Add,
-                    "-" => Operator::Sub,
-                    "*" => Operator::Mul,
-                    "/" => Operator::Div,
-                    _ => unreachable!(),
-                };
-
-                let key = captures[1].as_bytes().try_into().unwrap();
-                let value = Node::EquationNode(left, right, operator);
-                (key, value)
+            if let Some(captures) = re_equation.captures(x) {
+                let (_, [key, left, operator, right]) = captures.extract();
+                let operator = Op::try_from(operator.chars().next().unwrap()).unwrap();
+
+                (key, Definition::Binary(left, operator, right))
             } else {
-                let captures = re_value.captures(x).unwrap();
+                let (_, [key, value]) = re_value.captures(x).unwrap().extract();
 
-                let key = captures[1].as_bytes().try_into().unwrap();
-                let value = Node::ValueNode(captures[2].parse().unwrap());
-                (key, value)
+                (key, Definition::Value(value.parse().unwrap()))
             }
         })
         .collect()
 }
 
-fn solve_value(value: &u64) -> f64 {
-    *value as f64
-}
-
-fn solve_equation(
-    data: &HashMap<NodeId, Node>,
-    left: &NodeId,
-    right: &NodeId,
-    op: &Operator,
-) -> f64 {
-    let left_solved = solve_node(data, left);
-    let right_solved = solve_node(data, right);
-
-    match op {
-        Operator::Mul => left_solved * right_solved,
-        Operator::Div => left_solved / right_solved,
-        Operator::Add => left_solved + right_solved,
-        Operator::Sub => left_solved - right_solved,
-    }
-}
-
-fn solve_node(data: &HashMap<NodeId, Node>, node_id: &NodeId) -> f64 {
-    let node = &data[node_id];
-
-    match node {
-        Node::ValueNode(v) => solve_value(v),
-        Node::EquationNode(left, right, op) => solve_equation(data, left, right, op),
-    }
-}
-
-pub fn part_one(input: &str) -> Option<u64> {
+pub fn part_one(input: &str) -> Option<i64> {
     let data = parse_data(input);
 
-    let root_id: NodeId = "root".as_bytes().try_into().unwrap();
-
-    let result = solve_node(&data, &root_id) as u64;
+    let expr = Expr::build(&data, &"root", None).ok()?;
+    let result = expr.eval().ok()?.to_integer()?;
 
-    Some(result)
+    i64::try_from(result).ok()
 }
 
-pub fn part_two(input: &str) -> Option<u64> {
-    let mut data = parse_data(input);
-
-    let root_id: NodeId = "root".as_bytes().try_into().unwrap();
-    let humn_id: NodeId = "humn".as_bytes().try_into().unwrap();
+pub fn part_two(input: &str) -> Option<i64> {
+    let data = parse_data(input);
 
-    let (left_id, right_id) = match data[&root_id] {
-        Node::EquationNode(left, right, _) => (left, right),
-        Node::ValueNode(_) => return None,
+    let Definition::Binary(left, _, right) = data["root"] else {
+        return None;
     };
-    let right = solve_node(&data, &right_id);
-
-    let mut a = 0;
-    let mut b = u64::MAX;
-    loop {
-        let c = (a + b) / 2;
-
-        data.insert(humn_id, Node::ValueNode(c));
-        let left_c = solve_node(&data, &left_id) - right;
 
-        if left_c == 0_f64 {
-            return Some(c);
-        }
+    let equation = Equation::new(
+        Expr::build(&data, &left, Some(&"humn")).ok()?,
+        Expr::build(&data, &right, Some(&"humn")).ok()?,
+    );
+    let result = equation.solve().ok()?.to_integer()?;
 
-        data.insert(humn_id, Node::ValueNode(a));
-        let left_a = solve_node(&data, &left_id) - right;
-
-        if left_a.signum() == left_c.signum() {
-            a = c;
-        } else {
-            b = c;
-        }
-    }
+    i64::try_from(result).ok()
 }
 
 #[cfg(test)]
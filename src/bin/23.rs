@@ -1,17 +1,11 @@
 advent_of_code::solution!(23);
 
-use std::collections::HashMap;
 use std::collections::HashSet;
 
+use advent_of_code::majcn::automaton::{Automaton, Rule};
+use advent_of_code::majcn::point::Direction;
 use advent_of_code::majcn::point::Point;
 
-enum CardinalDirection {
-    North,
-    South,
-    East,
-    West,
-}
-
 fn parse_data(input: &str) -> HashSet<Point> {
     let mut result = HashSet::new();
     for (y, line) in input.lines().enumerate() {
@@ -24,113 +18,43 @@ fn parse_data(input: &str) -> HashSet<Point> {
     result
 }
 
-const NORTH: CardinalDirection = CardinalDirection::North;
-const SOUTH: CardinalDirection = CardinalDirection::South;
-const WEST: CardinalDirection = CardinalDirection::West;
-const EAST: CardinalDirection = CardinalDirection::East;
-const PRIORITIES: [[CardinalDirection; 4]; 4] = [
+const NORTH: Direction = Direction::Up;
+const SOUTH: Direction = Direction::Down;
+const WEST: Direction = Direction::Left;
+const EAST: Direction = Direction::Right;
+const PRIORITIES: [[Direction; 4]; 4] = [
     [NORTH, SOUTH, WEST, EAST],
     [SOUTH, WEST, EAST, NORTH],
     [WEST, EAST, NORTH, SOUTH],
     [EAST, NORTH, SOUTH, WEST],
 ];
 
-const NEIGHBORS_8: [Point; 8] = [
-    Point { x: -1, y: 0 },
-    Point { x: -1, y: 1 },
-    Point { x: 0, y: 1 },
-    Point { x: 1, y: 1 },
-    Point { x: 1, y: 0 },
-    Point { x: 1, y: -1 },
-    Point { x: 0, y: -1 },
-    Point { x: -1, y: -1 },
-];
+struct SpreadOut;
 
-fn get_next_location(elfs: &HashSet<Point>, elf: Point, priorities_index: usize) -> Option<Point> {
-    NEIGHBORS_8
-        .into_iter()
-        .map(|x| elf + x)
-        .find(|x| elfs.contains(x))?;
-
-    for priority in PRIORITIES[priorities_index].iter() {
-        match priority {
-            CardinalDirection::North => {
-                if (-1..=1).all(|x| !elfs.contains(&(elf + Point::new(x, -1)))) {
-                    return Some(elf + Point::new(0, -1));
-                }
-            }
-            CardinalDirection::South => {
-                if (-1..=1).all(|x| !elfs.contains(&(elf + Point::new(x, 1)))) {
-                    return Some(elf + Point::new(0, 1));
-                }
-            }
-            CardinalDirection::East => {
-                if (-1..=1).all(|y| !elfs.contains(&(elf + Point::new(1, y)))) {
-                    return Some(elf + Point::new(1, 0));
-                }
-            }
-            CardinalDirection::West => {
-                if (-1..=1).all(|y| !elfs.contains(&(elf + Point::new(-1, y)))) {
-                    return Some(elf + Point::new(-1, 0));
-                }
-            }
-        }
-    }
+impl Rule for SpreadOut {
+    fn propose(&self, elfs: &HashSet<Point>, elf: Point, round: usize) -> Option<Point> {
+        elf.neighbors_8().find(|x| elfs.contains(x))?;
 
-    None
-}
+        for priority in PRIORITIES[round % 4] {
+            let forward = elf + priority.screen_offset();
+            let side = priority.turn_right().screen_offset();
 
-fn part_x<F>(elfs: &HashSet<Point>, goal: F) -> (u32, HashSet<Point>)
-where
-    F: Fn(usize, bool) -> bool,
-{
-    let mut elfs = elfs.clone();
-    let mut i = 0;
-    loop {
-        let mut blocked_locations_set = HashSet::new();
-        let mut next_locations = HashMap::new();
-        for elf in elfs.iter().copied() {
-            if let Some(next_elf_location) = get_next_location(&elfs, elf, i % 4) {
-                if blocked_locations_set.contains(&next_elf_location) {
-                    continue;
-                }
-
-                if next_locations.contains_key(&next_elf_location) {
-                    next_locations.remove(&next_elf_location);
-                    blocked_locations_set.insert(next_elf_location);
-                    continue;
-                }
-
-                next_locations.insert(next_elf_location, elf);
+            if (-1..=1).all(|i| !elfs.contains(&(forward + side * i))) {
+                return Some(forward);
             }
         }
 
-        let next_locations_len = next_locations.len();
-
-        for (next_location, prev_location) in next_locations {
-            elfs.remove(&prev_location);
-            elfs.insert(next_location);
-        }
-
-        if goal(i + 1, next_locations_len == 0) {
-            return (i as u32 + 1, elfs);
-        }
-
-        i += 1;
+        None
     }
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
     let data = parse_data(input);
 
-    let (_, elfs) = part_x(&data, |i, _| i == 10);
+    let mut automaton = Automaton::new(data, SpreadOut);
+    automaton.run_until(|x| x.round == 10);
 
-    let minx = elfs.iter().map(|x| x.x).min().unwrap();
-    let maxx = elfs.iter().map(|x| x.x).max().unwrap();
-    let miny = elfs.iter().map(|x| x.y).min().unwrap();
-    let maxy = elfs.iter().map(|x| x.y).max().unwrap();
-
-    let result = (maxy - miny + 1) as u32 * (maxx - minx + 1) as u32 - elfs.len() as u32;
+    let result = automaton.empty_in_bounds() as u32;
 
     Some(result)
 }
@@ -138,7 +62,10 @@ pub fn part_one(input: &str) -> Option<u32> {
 pub fn part_two(input: &str) -> Option<u32> {
     let data = parse_data(input);
 
-    let (result, _) = part_x(&data, |_, nothing_happens| nothing_happens);
+    let mut automaton = Automaton::new(data, SpreadOut);
+    let info = automaton.run_until(|x| x.moved == 0);
+
+    let result = info.round as u32;
 
     Some(result)
 }
@@ -0,0 +1,289 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use crate::majcn::point::Point;
+
+pub trait Rule {
+    // where the cell wants to go this round, None keeps it in place
+    fn propose(&self, cells: &HashSet<Point>, cell: Point, round: usize) -> Option<Point>;
+}
+
+impl<F> Rule for F
+where
+    F: Fn(&HashSet<Point>, Point, usize) -> Option<Point>,
+{
+    fn propose(&self, cells: &HashSet<Point>, cell: Point, round: usize) -> Option<Point> {
+        self(cells, cell, round)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    // everybody proposes on the same state, proposals into occupied cells
+    // or to the same target are dropped
+    Synchronous,
+    // cells move one by one in reading order and see earlier moves of the same round
+    Sequential,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StepInfo {
+    // 1-based number of the finished round
+    pub round: usize,
+    pub moved: usize,
+}
+
+// smallest rectangle containing all cells, both corners inclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Bounds {
+    pub fn width(&self) -> usize {
+        (self.max.x - self.min.x + 1) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max.y - self.min.y + 1) as usize
+    }
+
+    pub fn area(&self) -> usize {
+        self.width() * self.height()
+    }
+}
+
+pub struct Automaton<R> {
+    cells: HashSet<Point>,
+    rule: R,
+    mode: Mode,
+    round: usize,
+    bounds: Option<Bounds>,
+    frames: Option<Vec<String>>,
+}
+
+impl<R: Rule> Automaton<R> {
+    pub fn new(cells: HashSet<Point>, rule: R) -> Self {
+        let mut result = Automaton {
+            cells,
+            rule,
+            mode: Mode::Synchronous,
+            round: 0,
+            bounds: None,
+            frames: None,
+        };
+        result.update_bounds();
+        result
+    }
+
+    pub fn with_mode(mut self, mode: Mode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    // renders the initial state and every following round
+    pub fn with_frames(mut self) -> Self {
+        self.frames = Some(vec![self.render()]);
+        self
+    }
+
+    pub fn cells(&self) -> &HashSet<Point> {
+        &self.cells
+    }
+
+    pub fn round(&self) -> usize {
+        self.round
+    }
+
+    pub fn bounds(&self) -> Option<Bounds> {
+        self.bounds
+    }
+
+    pub fn empty_in_bounds(&self) -> usize {
+        self.bounds.map_or(0, |x| x.area() - self.cells.len())
+    }
+
+    pub fn frames(&self) -> &[String] {
+        self.frames.as_deref().unwrap_or_default()
+    }
+
+    fn update_bounds(&mut self) {
+        self.bounds = self.cells.iter().fold(None, |acc, &p| match acc {
+            None => Some(Bounds { min: p, max: p }),
+            Some(b) => Some(Bounds {
+                min: Point::new(b.min.x.min(p.x), b.min.y.min(p.y)),
+                max: Point::new(b.max.x.max(p.x), b.max.y.max(p.y)),
+            }),
+        });
+    }
+
+    fn step_synchronous(&mut self) -> usize {
+        let mut proposals: HashMap<Point, Option<Point>> = HashMap::new();
+        for &cell in self.cells.iter() {
+            let proposal = self.rule.propose(&self.cells, cell, self.round);
+            if let Some(target) = proposal.filter(|x| !self.cells.contains(x)) {
+                proposals
+                    .entry(target)
+                    .and_modify(|x| *x = None)
+                    .or_insert(Some(cell));
+            }
+        }
+
+        let moves = proposals
+            .into_iter()
+            .filter_map(|(target, cell)| Some((cell?, target)))
+            .collect::<Vec<_>>();
+
+        for (cell, _) in moves.iter() {
+            self.cells.remove(cell);
+        }
+        for (_, target) in moves.iter() {
+            self.cells.insert(*target);
+        }
+
+        moves.len()
+    }
+
+    fn step_sequential(&mut self) -> usize {
+        let mut order = self.cells.iter().copied().collect::<Vec<_>>();
+        order.sort_unstable_by_key(|p| (p.y, p.x));
+
+        let mut moved = 0;
+        for cell in order {
+            if let Some(target) = self.rule.propose(&self.cells, cell, self.round) {
+                if !self.cells.contains(&target) {
+                    self.cells.remove(&cell);
+                    self.cells.insert(target);
+                    moved += 1;
+                }
+            }
+        }
+
+        moved
+    }
+
+    pub fn step(&mut self) -> StepInfo {
+        let moved = match self.mode {
+            Mode::Synchronous => self.step_synchronous(),
+            Mode::Sequential => self.step_sequential(),
+        };
+
+        self.round += 1;
+        self.update_bounds();
+
+        let frame = self.frames.is_some().then(|| self.render());
+        if let (Some(frames), Some(frame)) = (self.frames.as_mut(), frame) {
+            frames.push(frame);
+        }
+
+        StepInfo {
+            round: self.round,
+            moved,
+        }
+    }
+
+    // observer sees every finished round, stop is checked after it
+    pub fn run<S, O>(&mut self, mut stop: S, mut observer: O) -> StepInfo
+    where
+        S: FnMut(&StepInfo) -> bool,
+        O: FnMut(&Self, &StepInfo),
+    {
+        loop {
+            let info = self.step();
+            observer(self, &info);
+            if stop(&info) {
+                return info;
+            }
+        }
+    }
+
+    pub fn run_until<S>(&mut self, stop: S) -> StepInfo
+    where
+        S: FnMut(&StepInfo) -> bool,
+    {
+        self.run(stop, |_, _| {})
+    }
+
+    pub fn render(&self) -> String {
+        let Some(bounds) = self.bounds else {
+            return String::new();
+        };
+
+        let mut result = String::with_capacity((bounds.width() + 1) * bounds.height());
+        for y in bounds.min.y..=bounds.max.y {
+            for x in bounds.min.x..=bounds.max.x {
+                let c = if self.cells.contains(&Point::new(x, y)) {
+                    '#'
+                } else {
+                    '.'
+                };
+                result.push(c);
+            }
+            result.push('\n');
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(xs: &[i32]) -> HashSet<Point> {
+        xs.iter().map(|&x| Point::new(x, 0)).collect()
+    }
+
+    fn move_right(_: &HashSet<Point>, cell: Point, _: usize) -> Option<Point> {
+        (cell.x < 5).then(|| cell + Point::new(1, 0))
+    }
+
+    #[test]
+    fn test_modes() {
+        let move_left = |_: &HashSet<Point>, cell: Point, _: usize| {
+            (cell.x > 0).then(|| cell - Point::new(1, 0))
+        };
+
+        // only sequential moves see the cell freed earlier in the same round
+        let mut a = Automaton::new(row(&[1, 2]), move_left);
+        assert_eq!(a.step().moved, 1);
+        assert_eq!(a.cells(), &row(&[0, 2]));
+
+        let mut a = Automaton::new(row(&[1, 2]), move_left).with_mode(Mode::Sequential);
+        assert_eq!(a.step().moved, 2);
+        assert_eq!(a.cells(), &row(&[0, 1]));
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let towards_zero = |_: &HashSet<Point>, cell: Point, _: usize| {
+            (cell.x != 0).then(|| Point::new(cell.x - cell.x.signum(), 0))
+        };
+
+        let mut a = Automaton::new(row(&[-1, 1, 3]), towards_zero);
+        assert_eq!(a.step(), StepInfo { round: 1, moved: 1 });
+        assert_eq!(a.cells(), &row(&[-1, 1, 2]));
+    }
+
+    #[test]
+    fn test_run_and_frames() {
+        let mut a = Automaton::new(row(&[0, 2]), move_right).with_frames();
+
+        let mut moves = vec![];
+        let info = a.run(|x| x.moved == 0, |_, x| moves.push(x.moved));
+
+        assert_eq!(info.round, 5);
+        assert_eq!(moves, vec![2, 2, 2, 1, 0]);
+        assert_eq!(
+            a.bounds(),
+            Some(Bounds {
+                min: Point::new(4, 0),
+                max: Point::new(5, 0)
+            })
+        );
+        assert_eq!(a.frames()[0], "#.#\n");
+        assert_eq!(a.frames()[4], "##\n");
+        assert_eq!(a.frames().len(), 6);
+        assert_eq!(a.empty_in_bounds(), 0);
+    }
+}
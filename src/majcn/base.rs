@@ -0,0 +1,209 @@
+// positional system where digits[i] has value min_digit + i, like "=-012" with min_digit -2
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NumeralSystem {
+    digits: Vec<char>,
+    min_digit: i64,
+    negative_sign: Option<char>,
+}
+
+impl NumeralSystem {
+    pub fn new(digits: &str, min_digit: i64) -> Result<Self, String> {
+        let digits = digits.chars().collect::<Vec<_>>();
+
+        if digits.len() < 2 {
+            return Err(String::from("base must be at least 2"));
+        }
+        if min_digit > 0 || min_digit + (digits.len() as i64) <= 0 {
+            return Err(String::from("digits must include zero"));
+        }
+        if (1..digits.len()).any(|i| digits[..i].contains(&digits[i])) {
+            return Err(String::from("digits must be unique"));
+        }
+
+        Ok(NumeralSystem {
+            digits,
+            min_digit,
+            negative_sign: None,
+        })
+    }
+
+    // digits from 0 upwards, negative values get a sign prefix
+    pub fn standard(digits: &str) -> Result<Self, String> {
+        NumeralSystem::new(digits, 0)?.with_negative_sign('-')
+    }
+
+    // odd number of digits centered around zero
+    pub fn balanced(digits: &str) -> Result<Self, String> {
+        let base = digits.chars().count() as i64;
+        if base % 2 == 0 {
+            return Err(String::from("balanced system needs an odd base"));
+        }
+
+        NumeralSystem::new(digits, -(base - 1) / 2)
+    }
+
+    pub fn snafu() -> Self {
+        NumeralSystem::balanced("=-012").unwrap()
+    }
+
+    pub fn with_negative_sign(mut self, sign: char) -> Result<Self, String> {
+        if self.digits.contains(&sign) {
+            return Err(format!("sign {sign} is already a digit"));
+        }
+
+        self.negative_sign = Some(sign);
+        Ok(self)
+    }
+
+    #[inline]
+    pub fn base(&self) -> i64 {
+        self.digits.len() as i64
+    }
+
+    #[inline]
+    fn max_digit(&self) -> i64 {
+        self.min_digit + self.base() - 1
+    }
+
+    fn can_represent(&self, n: i128) -> bool {
+        (n >= 0 || self.min_digit < 0) && (n <= 0 || self.max_digit() > 0)
+    }
+
+    pub fn encode(&self, n: i64) -> Result<String, String> {
+        let n = n as i128;
+
+        if !self.can_represent(n) {
+            return match self.negative_sign {
+                Some(sign) if self.can_represent(-n) => {
+                    Ok(format!("{sign}{}", self.encode_digits(-n)))
+                }
+                _ => Err(format!("{n} can not be represented")),
+            };
+        }
+
+        Ok(self.encode_digits(n))
+    }
+
+    fn encode_digits(&self, n: i128) -> String {
+        let base = self.base() as i128;
+        let min_digit = self.min_digit as i128;
+
+        let mut result = vec![];
+        let mut n = n;
+        loop {
+            let digit = (n - min_digit).rem_euclid(base) + min_digit;
+            result.push(self.digits[(digit - min_digit) as usize]);
+
+            n = (n - digit) / base;
+            if n == 0 {
+                break;
+            }
+        }
+
+        result.iter().rev().collect()
+    }
+
+    pub fn decode(&self, s: &str) -> Result<i64, String> {
+        let (sign, digits) = match (self.negative_sign, s.chars().next()) {
+            (Some(sign), Some(c)) if c == sign => (-1, &s[c.len_utf8()..]),
+            _ => (1, s),
+        };
+
+        if digits.is_empty() {
+            return Err(String::from("no digits"));
+        }
+
+        // prefixes can go slightly past the final value, so accumulate in i128
+        let mut result: i128 = 0;
+        for c in digits.chars() {
+            let value = self
+                .digits
+                .iter()
+                .position(|x| *x == c)
+                .ok_or_else(|| format!("unknown digit: {c}"))? as i128
+                + self.min_digit as i128;
+
+            result = result
+                .checked_mul(self.base() as i128)
+                .and_then(|x| x.checked_add(value))
+                .ok_or("overflow")?;
+        }
+
+        i64::try_from(sign * result).map_err(|_| String::from("overflow"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snafu() {
+        let snafu = NumeralSystem::snafu();
+        let pairs = [
+            (0, "0"),
+            (3, "1="),
+            (2022, "1=11-2"),
+            (12345, "1-0---0"),
+            (314159265, "1121-1110-1=0"),
+            (-8, "=2"),
+        ];
+
+        for (n, s) in pairs {
+            assert_eq!(snafu.encode(n), Ok(String::from(s)));
+            assert_eq!(snafu.decode(s), Ok(n));
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let systems = [
+            NumeralSystem::snafu(),
+            NumeralSystem::balanced("T01").unwrap(),
+            NumeralSystem::standard("01").unwrap(),
+            NumeralSystem::standard("0123456789abcdef").unwrap(),
+            NumeralSystem::new("ab0cd", -2).unwrap(),
+            NumeralSystem::new("a0bcdefg", -1).unwrap(),
+        ];
+
+        // xorshift, spread evenly over the whole i64 range
+        let random = std::iter::successors(Some(0x9E37_79B9_7F4A_7C15_u64), |&x| {
+            let x = x ^ (x << 13);
+            let x = x ^ (x >> 7);
+            Some(x ^ (x << 17))
+        })
+        .map(|x| x as i64)
+        .take(2000);
+
+        // powers of two with their neighbours, and both ends of the range
+        let edges = (0..63)
+            .map(|i| 1_i64 << i)
+            .flat_map(|x| [x - 1, x, x + 1, -x - 1, -x, -x + 1])
+            .chain((0..8).flat_map(|i| [i64::MIN + i, i64::MAX - i]));
+
+        let values = (-2000..2000).chain(random).chain(edges);
+
+        for system in systems.iter() {
+            for n in values.clone() {
+                let encoded = system.encode(n).unwrap();
+                assert_eq!(system.decode(&encoded), Ok(n), "{encoded}");
+            }
+        }
+
+        assert_eq!(
+            NumeralSystem::standard("01").unwrap().encode(-5),
+            Ok(String::from("-101"))
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(NumeralSystem::new("123", 1).is_err());
+        assert!(NumeralSystem::new("0", 0).is_err());
+        assert!(NumeralSystem::new("001", 0).is_err());
+        assert!(NumeralSystem::balanced("0123").is_err());
+        assert!(NumeralSystem::new("012", 0).unwrap().encode(-1).is_err());
+        assert!(NumeralSystem::snafu().decode("12a").is_err());
+        assert!(NumeralSystem::snafu().decode("").is_err());
+    }
+}
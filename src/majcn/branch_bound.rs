@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::hash::Hash;
+
+pub trait Problem {
+    type State: Clone + Hash + Eq;
+    type Key: Hash + Eq;
+    type Value: Ord + Copy;
+
+    fn successors(&self, state: &Self::State) -> Vec<Self::State>;
+
+    // value of the state if the search stops here
+    fn objective(&self, state: &Self::State) -> Self::Value;
+
+    // no state reachable from here scores more
+    fn bound(&self, state: &Self::State) -> Self::Value;
+
+    // only states with the same key are compared for dominance
+    fn dominance_key(&self, _state: &Self::State) -> Option<Self::Key> {
+        None
+    }
+
+    // a is at least as good as b in every future
+    fn dominates(&self, _a: &Self::State, _b: &Self::State) -> bool {
+        false
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub expanded: usize,
+    pub pruned: usize,
+    pub duplicates: usize,
+    pub dominated: usize,
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "expanded {}, pruned {}, duplicates {}, dominated {}",
+            self.expanded, self.pruned, self.duplicates, self.dominated
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Solution<S, V> {
+    pub state: S,
+    pub value: V,
+    pub stats: Stats,
+}
+
+pub struct BranchAndBound<'a, P: Problem> {
+    problem: &'a P,
+    memo: bool,
+}
+
+impl<'a, P: Problem> BranchAndBound<'a, P> {
+    pub fn new(problem: &'a P) -> Self {
+        BranchAndBound {
+            problem,
+            memo: true,
+        }
+    }
+
+    // skips remembering expanded states, for problems where they rarely repeat
+    pub fn without_memo(mut self) -> Self {
+        self.memo = false;
+        self
+    }
+
+    // depth first, successors are tried in the order they are generated
+    pub fn solve(&self, init: P::State) -> Solution<P::State, P::Value> {
+        let mut best = (self.problem.objective(&init), init.clone());
+
+        let stats = self.search(init, Some(&mut best), |_| {});
+
+        Solution {
+            state: best.1,
+            value: best.0,
+            stats,
+        }
+    }
+
+    // visits every state which is not a duplicate or dominated, bounds are ignored
+    pub fn explore<F>(&self, init: P::State, observer: F) -> Stats
+    where
+        F: FnMut(&P::State),
+    {
+        self.search(init, None, observer)
+    }
+
+    fn search<F>(
+        &self,
+        init: P::State,
+        mut best: Option<&mut (P::Value, P::State)>,
+        mut observer: F,
+    ) -> Stats
+    where
+        F: FnMut(&P::State),
+    {
+        let mut stats = Stats::default();
+        let mut seen = HashSet::new();
+        let mut frontier: HashMap<P::Key, Vec<P::State>> = HashMap::new();
+
+        let mut stack = vec![init];
+        while let Some(state) = stack.pop() {
+            if let Some((value, _)) = best.as_deref() {
+                if self.problem.bound(&state) <= *value {
+                    stats.pruned += 1;
+                    continue;
+                }
+            }
+
+            if self.memo && !seen.insert(state.clone()) {
+                stats.duplicates += 1;
+                continue;
+            }
+
+            if let Some(key) = self.problem.dominance_key(&state) {
+                let others = frontier.entry(key).or_default();
+                if others.iter().any(|x| self.problem.dominates(x, &state)) {
+                    stats.dominated += 1;
+                    continue;
+                }
+                others.retain(|x| !self.problem.dominates(&state, x));
+                others.push(state.clone());
+            }
+
+            stats.expanded += 1;
+            observer(&state);
+
+            if let Some((value, best_state)) = best.as_deref_mut() {
+                let objective = self.problem.objective(&state);
+                if objective > *value {
+                    *value = objective;
+                    *best_state = state.clone();
+                }
+            }
+
+            let successors = self.problem.successors(&state);
+            stack.extend(successors.into_iter().rev());
+        }
+
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0/1 knapsack, state is (next item, weight, value)
+    struct Knapsack {
+        items: Vec<(u32, u32)>,
+        capacity: u32,
+    }
+
+    impl Problem for Knapsack {
+        type State = (usize, u32, u32);
+        type Key = (usize, u32);
+        type Value = u32;
+
+        fn successors(&self, &(i, w, v): &Self::State) -> Vec<Self::State> {
+            let Some(&(iw, iv)) = self.items.get(i) else {
+                return vec![];
+            };
+
+            let mut result = vec![];
+            if w + iw <= self.capacity {
+                result.push((i + 1, w + iw, v + iv));
+            }
+            result.push((i + 1, w, v));
+            result
+        }
+
+        fn objective(&self, state: &Self::State) -> u32 {
+            state.2
+        }
+
+        fn bound(&self, &(i, _, v): &Self::State) -> u32 {
+            v + self.items[i..].iter().map(|x| x.1).sum::<u32>()
+        }
+
+        fn dominance_key(&self, &(i, w, _): &Self::State) -> Option<Self::Key> {
+            Some((i, w))
+        }
+
+        fn dominates(&self, a: &Self::State, b: &Self::State) -> bool {
+            a.2 >= b.2
+        }
+    }
+
+    fn knapsack() -> Knapsack {
+        Knapsack {
+            items: vec![(5, 10), (4, 40), (6, 30), (3, 50), (1, 1), (1, 1), (2, 2)],
+            capacity: 10,
+        }
+    }
+
+    #[test]
+    fn test_solve() {
+        let problem = knapsack();
+        let solution = BranchAndBound::new(&problem).solve((0, 0, 0));
+
+        assert_eq!(solution.value, 93);
+        assert_eq!(solution.state, (7, 10, 93));
+        assert!(solution.stats.pruned > 0);
+        assert!(solution.stats.expanded < 1 << 7);
+    }
+
+    #[test]
+    fn test_explore() {
+        let problem = knapsack();
+
+        let mut best = 0;
+        let stats = BranchAndBound::new(&problem)
+            .without_memo()
+            .explore((0, 0, 0), |x| {
+                if x.0 == 7 {
+                    best = best.max(x.2);
+                }
+            });
+
+        assert_eq!(stats.pruned, 0);
+        assert_eq!(stats.duplicates, 0);
+        assert!(stats.dominated > 0);
+        // dominated states are skipped but the optimum is still visited
+        assert_eq!(best, 93);
+        assert_eq!(
+            stats.to_string(),
+            format!(
+                "expanded {}, pruned 0, duplicates 0, dominated {}",
+                stats.expanded, stats.dominated
+            )
+        );
+    }
+}
@@ -0,0 +1,320 @@
+use std::collections::VecDeque;
+
+use crate::majcn::list::Array2D;
+use crate::majcn::point::Direction;
+use crate::majcn::point3::Point3;
+
+// orientation of a face on the folded cube, right and down follow the map axes
+#[derive(Debug, Clone, Copy)]
+struct Orientation {
+    normal: Point3,
+    right: Point3,
+    down: Point3,
+}
+
+impl Orientation {
+    fn edge(&self, direction: Direction) -> Point3 {
+        match direction {
+            Direction::Up => -self.down,
+            Direction::Right => self.right,
+            Direction::Down => self.down,
+            Direction::Left => -self.right,
+        }
+    }
+
+    // rolls the cube over the edge in direction
+    fn roll(&self, direction: Direction) -> Self {
+        let Orientation {
+            normal,
+            right,
+            down,
+        } = *self;
+
+        match direction {
+            Direction::Up => Orientation::new(-down, right, normal),
+            Direction::Right => Orientation::new(right, -normal, down),
+            Direction::Down => Orientation::new(down, right, -normal),
+            Direction::Left => Orientation::new(-right, normal, down),
+        }
+    }
+
+    fn new(normal: Point3, right: Point3, down: Point3) -> Self {
+        Orientation {
+            normal,
+            right,
+            down,
+        }
+    }
+}
+
+// map of 6 square faces that folds into a cube, works for any of the 11 nets
+pub struct CubeNet {
+    size: usize,
+    faces: Vec<(usize, usize)>,
+    face_grid: Array2D<Option<usize>>,
+    // face across every edge and the side of that face we enter through
+    edges: Vec<[(usize, Direction); 4]>,
+}
+
+impl CubeNet {
+    pub fn from_grid<T, F>(grid: &Array2D<T>, is_face: F) -> Result<Self, String>
+    where
+        F: Fn(&T) -> bool,
+    {
+        let count = grid.iter_keys().filter(|&key| is_face(&grid[key])).count();
+        let size = (1..)
+            .find(|s| 6 * s * s >= count)
+            .filter(|s| 6 * s * s == count)
+            .ok_or_else(|| format!("{count} cells can not form 6 square faces"))?;
+
+        if !grid.len().is_multiple_of(size) || !grid.len_line().is_multiple_of(size) {
+            return Err(String::from("map is not aligned to the face size"));
+        }
+
+        let (tiles_x, tiles_y) = (grid.len_line() / size, grid.len() / size);
+        let mut faces = vec![];
+        let mut face_grid = Array2D::new(tiles_x);
+        for ty in 0..tiles_y {
+            let mut line = vec![];
+            for tx in 0..tiles_x {
+                let cells = (0..size * size)
+                    .filter(|i| is_face(&grid[(tx * size + i % size, ty * size + i / size)]))
+                    .count();
+
+                match cells {
+                    0 => line.push(None),
+                    c if c == size * size => {
+                        line.push(Some(faces.len()));
+                        faces.push((tx * size, ty * size));
+                    }
+                    _ => return Err(format!("tile ({tx}, {ty}) is partially filled")),
+                }
+            }
+            face_grid.add_line(line);
+        }
+
+        let mut net = CubeNet {
+            size,
+            faces,
+            face_grid,
+            edges: vec![],
+        };
+        net.fold()?;
+
+        Ok(net)
+    }
+
+    fn neighbor_tile(&self, face: usize, direction: Direction) -> Option<usize> {
+        let (x, y) = self.faces[face];
+        let (tx, ty) = ((x / self.size) as i32, (y / self.size) as i32);
+        let offset = direction.screen_offset();
+        let (nx, ny) = (tx + offset.x, ty + offset.y);
+
+        if nx < 0 || ny < 0 || nx as usize >= self.face_grid.len_line() {
+            return None;
+        }
+        if ny as usize >= self.face_grid.len() {
+            return None;
+        }
+
+        self.face_grid[(nx as usize, ny as usize)]
+    }
+
+    fn fold(&mut self) -> Result<(), String> {
+        let mut orientations: Vec<Option<Orientation>> = vec![None; self.faces.len()];
+        orientations[0] = Some(Orientation::new(
+            Point3::new(0, 0, -1),
+            Point3::new(1, 0, 0),
+            Point3::new(0, 1, 0),
+        ));
+
+        let mut queue = VecDeque::from([0]);
+        while let Some(face) = queue.pop_front() {
+            let orientation = orientations[face].unwrap();
+            for direction in Direction::ALL {
+                if let Some(next) = self.neighbor_tile(face, direction) {
+                    if orientations[next].is_none() {
+                        orientations[next] = Some(orientation.roll(direction));
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+
+        let orientations = orientations
+            .into_iter()
+            .collect::<Option<Vec<_>>>()
+            .ok_or("faces are not connected")?;
+
+        let face_with_normal = |normal: Point3| {
+            orientations
+                .iter()
+                .position(|x| x.normal == normal)
+                .ok_or("faces overlap when folded")
+        };
+
+        for orientation in orientations.iter() {
+            let mut edges = [(0, Direction::Up); 4];
+            for (i, direction) in Direction::ALL.into_iter().enumerate() {
+                let next = face_with_normal(orientation.edge(direction))?;
+                let side = Direction::ALL
+                    .into_iter()
+                    .find(|&d| orientations[next].edge(d) == orientation.normal)
+                    .unwrap();
+                edges[i] = (next, side);
+            }
+            self.edges.push(edges);
+        }
+
+        Ok(())
+    }
+
+    pub fn face_size(&self) -> usize {
+        self.size
+    }
+
+    // top left cell of every face
+    pub fn faces(&self) -> &[(usize, usize)] {
+        &self.faces
+    }
+
+    pub fn face_of(&self, x: usize, y: usize) -> Option<usize> {
+        if x >= self.face_grid.len_line() * self.size || y >= self.face_grid.len() * self.size {
+            return None;
+        }
+
+        self.face_grid[(x / self.size, y / self.size)]
+    }
+
+    // face across the edge and the direction of travel after crossing it
+    pub fn neighbor(&self, face: usize, direction: Direction) -> (usize, Direction) {
+        let (next, side) = self.edges[face][direction as usize];
+        (next, side.opposite())
+    }
+
+    // one step over the surface of the folded cube
+    pub fn step(&self, x: usize, y: usize, direction: Direction) -> (usize, usize, Direction) {
+        let s = self.size;
+        let face = self.face_of(x, y).expect("position is not on a face");
+        let (fx, fy) = self.faces[face];
+        let (lx, ly) = (x - fx, y - fy);
+
+        let inside = match direction {
+            Direction::Up => (ly > 0).then(|| (lx, ly - 1)),
+            Direction::Right => (lx < s - 1).then(|| (lx + 1, ly)),
+            Direction::Down => (ly < s - 1).then(|| (lx, ly + 1)),
+            Direction::Left => (lx > 0).then(|| (lx - 1, ly)),
+        };
+        if let Some((lx, ly)) = inside {
+            return (fx + lx, fy + ly, direction);
+        }
+
+        // positions along an edge are counted clockwise, shared edges run in opposite directions
+        let offset = match direction {
+            Direction::Up => lx,
+            Direction::Right => ly,
+            Direction::Down => s - 1 - lx,
+            Direction::Left => s - 1 - ly,
+        };
+
+        let (next, side) = self.edges[face][direction as usize];
+        let j = s - 1 - offset;
+        let (lx, ly) = match side {
+            Direction::Up => (j, 0),
+            Direction::Right => (s - 1, j),
+            Direction::Down => (s - 1 - j, s - 1),
+            Direction::Left => (0, s - 1 - j),
+        };
+
+        let (fx, fy) = self.faces[next];
+        (fx + lx, fy + ly, side.opposite())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand(net: &str, size: usize) -> Array2D<bool> {
+        let lines = net.lines().collect::<Vec<_>>();
+        let width = lines.iter().map(|x| x.len()).max().unwrap();
+
+        let mut result = Array2D::new(width * size);
+        for line in lines {
+            let tiles = line
+                .bytes()
+                .chain(std::iter::repeat(b'.'))
+                .take(width)
+                .collect::<Vec<_>>();
+            for _ in 0..size {
+                result.add_line(
+                    tiles
+                        .iter()
+                        .flat_map(|&x| std::iter::repeat_n(x == b'#', size)),
+                );
+            }
+        }
+        result
+    }
+
+    const NETS: [&str; 11] = [
+        "#...\n####\n#...",
+        "#...\n####\n.#..",
+        "#...\n####\n..#.",
+        "#...\n####\n...#",
+        ".#..\n####\n.#..",
+        ".#..\n####\n..#.",
+        "##..\n.###\n.#..",
+        "##..\n.###\n..#.",
+        "##..\n.###\n...#",
+        "##..\n.##.\n..##",
+        "###..\n..###",
+    ];
+
+    #[test]
+    fn test_example_net() {
+        let net = CubeNet::from_grid(&expand("..#.\n###.\n..##", 4), |&x| x).unwrap();
+
+        assert_eq!(net.face_size(), 4);
+        assert_eq!(net.step(11, 5, Direction::Right), (14, 8, Direction::Down));
+        assert_eq!(net.step(10, 11, Direction::Down), (1, 7, Direction::Up));
+        assert_eq!(net.step(6, 4, Direction::Up), (8, 2, Direction::Right));
+        assert_eq!(net.step(5, 5, Direction::Right), (6, 5, Direction::Right));
+    }
+
+    #[test]
+    fn test_all_nets() {
+        for shape in NETS {
+            let grid = expand(shape, 3);
+            let net = CubeNet::from_grid(&grid, |&x| x).unwrap();
+
+            // every walk around the cube comes back after four faces
+            for key in grid.iter_keys().filter(|&key| grid[key]) {
+                for direction in Direction::ALL {
+                    let mut state = (key.0, key.1, direction);
+                    for _ in 0..4 * 3 {
+                        state = net.step(state.0, state.1, state.2);
+                    }
+                    assert_eq!(state, (key.0, key.1, direction), "{shape}");
+                }
+            }
+
+            for face in 0..6 {
+                for direction in Direction::ALL {
+                    let (next, d) = net.neighbor(face, direction);
+                    assert_eq!(
+                        net.neighbor(next, d.opposite()),
+                        (face, direction.opposite())
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid_nets() {
+        assert!(CubeNet::from_grid(&expand("####\n####", 2), |&x| x).is_err());
+        assert!(CubeNet::from_grid(&expand("######", 2), |&x| x).is_err());
+        assert!(CubeNet::from_grid(&expand("###\n..#\n.#.\n.#.", 2), |&x| x).is_err());
+    }
+}
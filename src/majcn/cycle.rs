@@ -0,0 +1,259 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::Add;
+use std::ops::Mul;
+use std::ops::Sub;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    pub fn new(start: usize, length: usize) -> Self {
+        Cycle { start, length }
+    }
+
+    // step inside the first repetition which is equivalent to step n
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+
+    // history[i] is the value after step i and has to cover at least start + length steps
+    pub fn extrapolate<T>(&self, history: &[T], n: usize) -> T
+    where
+        T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + TryFrom<usize>,
+        <T as TryFrom<usize>>::Error: std::fmt::Debug,
+    {
+        if n < self.start {
+            return history[n];
+        }
+
+        let repetitions = T::try_from((n - self.start) / self.length).unwrap();
+        let diff_per_repetition = history[self.start + self.length] - history[self.start];
+
+        history[self.reduce(n)] + repetitions * diff_per_repetition
+    }
+}
+
+pub struct CycleDetector<K> {
+    seen: HashMap<K, usize>,
+}
+
+impl<K: Hash + Eq> CycleDetector<K> {
+    pub fn new() -> Self {
+        CycleDetector {
+            seen: HashMap::new(),
+        }
+    }
+
+    pub fn observe(&mut self, step: usize, key: K) -> Option<Cycle> {
+        match self.seen.entry(key) {
+            Entry::Occupied(prev_step) => {
+                let start = *prev_step.get();
+                Some(Cycle::new(start, step - start))
+            }
+            Entry::Vacant(o) => {
+                o.insert(step);
+                None
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.seen.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty()
+    }
+}
+
+impl<K: Hash + Eq> Default for CycleDetector<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn find_cycle<T, K, F, G>(init: T, step_f: F, key_f: G) -> Cycle
+where
+    K: Hash + Eq,
+    F: Fn(&T) -> T,
+    G: Fn(&T) -> K,
+{
+    let mut detector = CycleDetector::new();
+
+    let mut state = init;
+    let mut step = 0;
+    loop {
+        if let Some(cycle) = detector.observe(step, key_f(&state)) {
+            return cycle;
+        }
+
+        state = step_f(&state);
+        step += 1;
+    }
+}
+
+pub fn floyd<T, F>(init: T, step_f: F) -> Cycle
+where
+    T: Clone + PartialEq,
+    F: Fn(&T) -> T,
+{
+    let mut tortoise = step_f(&init);
+    let mut hare = step_f(&tortoise);
+    while tortoise != hare {
+        tortoise = step_f(&tortoise);
+        hare = step_f(&step_f(&hare));
+    }
+
+    let mut start = 0;
+    tortoise = init;
+    while tortoise != hare {
+        tortoise = step_f(&tortoise);
+        hare = step_f(&hare);
+        start += 1;
+    }
+
+    let mut length = 1;
+    hare = step_f(&tortoise);
+    while tortoise != hare {
+        hare = step_f(&hare);
+        length += 1;
+    }
+
+    Cycle::new(start, length)
+}
+
+pub fn brent<T, F>(init: T, step_f: F) -> Cycle
+where
+    T: Clone + PartialEq,
+    F: Fn(&T) -> T,
+{
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = init.clone();
+    let mut hare = step_f(&init);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step_f(&hare);
+        length += 1;
+    }
+
+    tortoise = init.clone();
+    hare = init;
+    for _ in 0..length {
+        hare = step_f(&hare);
+    }
+
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step_f(&tortoise);
+        hare = step_f(&hare);
+        start += 1;
+    }
+
+    Cycle::new(start, length)
+}
+
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Memo {
+            cache: HashMap::new(),
+        }
+    }
+
+    // f receives the memo itself, so recursive definitions hit the cache as well
+    pub fn get<F>(&mut self, key: K, f: F) -> V
+    where
+        F: FnOnce(&mut Self, &K) -> V,
+    {
+        if let Some(v) = self.cache.get(&key) {
+            return v.clone();
+        }
+
+        let v = f(self, &key);
+        self.cache.insert(key, v.clone());
+        v
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 -> 1 -> 2 -> 3 -> 4 -> 5 -> 2 -> ...
+    fn step(x: &u32) -> u32 {
+        if *x == 5 {
+            2
+        } else {
+            x + 1
+        }
+    }
+
+    #[test]
+    fn test_detectors_agree() {
+        let expected = Cycle::new(2, 4);
+
+        assert_eq!(floyd(0, step), expected);
+        assert_eq!(brent(0, step), expected);
+        assert_eq!(find_cycle(0, step, |x| *x), expected);
+    }
+
+    #[test]
+    fn test_extrapolate() {
+        // value grows by 10 every cycle of length 3 starting at step 1
+        let history = [0_u64, 5, 7, 12, 15, 17, 22];
+        let cycle = Cycle::new(1, 3);
+
+        assert_eq!(cycle.extrapolate(&history, 0), 0);
+        assert_eq!(cycle.extrapolate(&history, 5), 17);
+        assert_eq!(cycle.extrapolate(&history, 7), 25);
+        assert_eq!(cycle.extrapolate(&history, 1_000_000), 3333335);
+    }
+
+    #[test]
+    fn test_memo() {
+        fn fib(memo: &mut Memo<u64, u64>, n: u64) -> u64 {
+            memo.get(n, |memo, &n| {
+                if n < 2 {
+                    n
+                } else {
+                    fib(memo, n - 1) + fib(memo, n - 2)
+                }
+            })
+        }
+
+        let mut memo = Memo::new();
+        assert_eq!(fib(&mut memo, 90), 2880067194370816120);
+        assert_eq!(memo.len(), 91);
+    }
+}
@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::hash::Hash;
+
+use crate::majcn::num::Rational;
+
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl Op {
+    pub fn apply(&self, left: Rational, right: Rational) -> Result<Rational, String> {
+        let result = match self {
+            Op::Add => left.checked_add(right),
+            Op::Sub => left.checked_sub(right),
+            Op::Mul => left.checked_mul(right),
+            Op::Div if right.num == 0 => return Err(String::from("division by zero")),
+            Op::Div => left.checked_div(right),
+        };
+
+        result.ok_or_else(|| String::from("overflow"))
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            Op::Add | Op::Sub => 1,
+            Op::Mul | Op::Div => 2,
+        }
+    }
+
+    fn symbol(&self) -> char {
+        match self {
+            Op::Add => '+',
+            Op::Sub => '-',
+            Op::Mul => '*',
+            Op::Div => '/',
+        }
+    }
+}
+
+impl TryFrom<char> for Op {
+    type Error = String;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            '+' => Ok(Op::Add),
+            '-' => Ok(Op::Sub),
+            '*' => Ok(Op::Mul),
+            '/' => Ok(Op::Div),
+            _ => Err(format!("unknown operator: {value}")),
+        }
+    }
+}
+
+// one named node of a job list, like "root: pppw + sjmn" or "dbpl: 5"
+pub enum Definition<K> {
+    Value(i128),
+    Binary(K, Op, K),
+}
+
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub enum Expr {
+    Const(Rational),
+    Var(String),
+    Binary(Box<Expr>, Op, Box<Expr>),
+}
+
+impl Expr {
+    // node equal to unknown becomes a variable regardless of its definition
+    pub fn build<K>(
+        definitions: &HashMap<K, Definition<K>>,
+        root: &K,
+        unknown: Option<&K>,
+    ) -> Result<Expr, String>
+    where
+        K: Hash + Eq + Display,
+    {
+        if unknown == Some(root) {
+            return Ok(Expr::Var(root.to_string()));
+        }
+
+        match definitions.get(root) {
+            Some(Definition::Value(v)) => Ok(Expr::Const(Rational::integer(*v))),
+            Some(Definition::Binary(left, op, right)) => Ok(Expr::Binary(
+                Box::new(Expr::build(definitions, left, unknown)?),
+                *op,
+                Box::new(Expr::build(definitions, right, unknown)?),
+            )),
+            None => Err(format!("undefined node: {root}")),
+        }
+    }
+
+    pub fn count_vars(&self) -> usize {
+        match self {
+            Expr::Const(_) => 0,
+            Expr::Var(_) => 1,
+            Expr::Binary(left, _, right) => left.count_vars() + right.count_vars(),
+        }
+    }
+
+    pub fn eval(&self) -> Result<Rational, String> {
+        match self {
+            Expr::Const(v) => Ok(*v),
+            Expr::Var(name) => Err(format!("unknown value of {name}")),
+            Expr::Binary(left, op, right) => op.apply(left.eval()?, right.eval()?),
+        }
+    }
+
+    pub fn eval_with(&self, value: Rational) -> Result<Rational, String> {
+        match self {
+            Expr::Const(v) => Ok(*v),
+            Expr::Var(_) => Ok(value),
+            Expr::Binary(left, op, right) => {
+                op.apply(left.eval_with(value)?, right.eval_with(value)?)
+            }
+        }
+    }
+
+    // folds every subtree without a variable into a constant
+    pub fn simplify(&self) -> Result<Expr, String> {
+        match self {
+            Expr::Binary(left, op, right) => {
+                let left = left.simplify()?;
+                let right = right.simplify()?;
+                match (&left, &right) {
+                    (Expr::Const(l), Expr::Const(r)) => Ok(Expr::Const(op.apply(*l, *r)?)),
+                    _ => Ok(Expr::Binary(Box::new(left), *op, Box::new(right))),
+                }
+            }
+            x => Ok(x.clone()),
+        }
+    }
+
+    fn fmt_with_parent(
+        &self,
+        f: &mut Formatter<'_>,
+        parent: u8,
+        is_right: bool,
+    ) -> std::fmt::Result {
+        match self {
+            Expr::Const(v) if v.is_integer() => write!(f, "{v}"),
+            Expr::Const(v) => write!(f, "({v})"),
+            Expr::Var(name) => write!(f, "{name}"),
+            Expr::Binary(left, op, right) => {
+                let p = op.precedence();
+                let parens = p < parent || (p == parent && is_right);
+                if parens {
+                    write!(f, "(")?;
+                }
+                left.fmt_with_parent(f, p, false)?;
+                write!(f, " {} ", op.symbol())?;
+                right.fmt_with_parent(f, p, true)?;
+                if parens {
+                    write!(f, ")")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.fmt_with_parent(f, 0, false)
+    }
+}
+
+pub struct Equation {
+    pub left: Expr,
+    pub right: Expr,
+}
+
+impl Equation {
+    pub fn new(left: Expr, right: Expr) -> Self {
+        Equation { left, right }
+    }
+
+    pub fn simplify(&self) -> Result<Equation, String> {
+        Ok(Equation::new(self.left.simplify()?, self.right.simplify()?))
+    }
+
+    // exact when the variable appears exactly once, by inverting operations along its path
+    pub fn solve(&self) -> Result<Rational, String> {
+        let (mut expr, known) = match (self.left.count_vars(), self.right.count_vars()) {
+            (1, 0) => (&self.left, &self.right),
+            (0, 1) => (&self.right, &self.left),
+            (0, 0) => return Err(String::from("equation has no unknown")),
+            _ => return Err(String::from("unknown must appear exactly once")),
+        };
+
+        let mut target = known.eval()?;
+        while let Expr::Binary(left, op, right) = expr {
+            if left.count_vars() == 1 {
+                let k = right.eval()?;
+                target = match op {
+                    Op::Add => Op::Sub.apply(target, k)?,
+                    Op::Sub => Op::Add.apply(target, k)?,
+                    Op::Mul => Op::Div.apply(target, k)?,
+                    Op::Div => Op::Mul.apply(target, k)?,
+                };
+                expr = left;
+            } else {
+                let k = left.eval()?;
+                target = match op {
+                    Op::Add => Op::Sub.apply(target, k)?,
+                    Op::Sub => Op::Sub.apply(k, target)?,
+                    Op::Mul => Op::Div.apply(target, k)?,
+                    Op::Div => Op::Div.apply(k, target)?,
+                };
+                expr = right;
+            }
+        }
+
+        Ok(target)
+    }
+}
+
+impl Display for Equation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} = {}", self.left, self.right)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn definitions() -> HashMap<&'static str, Definition<&'static str>> {
+        HashMap::from([
+            ("root", Definition::Binary("a", Op::Div, "b")),
+            ("a", Definition::Binary("c", Op::Sub, "x")),
+            ("b", Definition::Binary("d", Op::Mul, "d")),
+            ("c", Definition::Value(10)),
+            ("d", Definition::Value(2)),
+            ("x", Definition::Value(3)),
+        ])
+    }
+
+    #[test]
+    fn test_eval_and_print() {
+        let defs = definitions();
+
+        let expr = Expr::build(&defs, &"root", None).unwrap();
+        assert_eq!(expr.eval(), Ok(Rational::new(7, 4)));
+        assert_eq!(expr.to_string(), "(10 - 3) / (2 * 2)");
+
+        let expr = Expr::build(&defs, &"root", Some(&"x")).unwrap();
+        assert!(expr.eval().is_err());
+        assert_eq!(expr.simplify().unwrap().to_string(), "(10 - x) / 4");
+        assert_eq!(
+            expr.eval_with(Rational::integer(2)),
+            Ok(Rational::integer(2))
+        );
+    }
+
+    #[test]
+    fn test_solve() {
+        let defs = definitions();
+
+        let left = Expr::Const(Rational::integer(5));
+        let right = Expr::build(&defs, &"root", Some(&"x")).unwrap();
+        let equation = Equation::new(left, right);
+        assert_eq!(equation.solve(), Ok(Rational::integer(-10)));
+        assert_eq!(equation.simplify().unwrap().to_string(), "5 = (10 - x) / 4");
+
+        let twice = Equation::new(
+            Expr::Binary(
+                Box::new(Expr::Var(String::from("x"))),
+                Op::Add,
+                Box::new(Expr::Var(String::from("x"))),
+            ),
+            Expr::Const(Rational::integer(1)),
+        );
+        assert!(twice.solve().is_err());
+    }
+}
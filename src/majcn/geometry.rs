@@ -0,0 +1,435 @@
+use crate::majcn::interval::Interval;
+use crate::majcn::num::gcd;
+use crate::majcn::point::Point;
+
+// point with rational coordinates x / den and y / den, den is always positive,
+// i128 holds every intersection of lines with i64 coefficients
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub struct RationalPoint {
+    pub x: i128,
+    pub y: i128,
+    pub den: i128,
+}
+
+impl RationalPoint {
+    pub fn new(x: i128, y: i128, den: i128) -> Self {
+        assert_ne!(den, 0, "division by zero!");
+
+        let sign = den.signum();
+        let d = gcd(gcd(x.unsigned_abs(), y.unsigned_abs()), den.unsigned_abs()) as i128;
+
+        RationalPoint {
+            x: sign * (x / d),
+            y: sign * (y / d),
+            den: sign * (den / d),
+        }
+    }
+
+    #[inline]
+    pub fn is_integral(&self) -> bool {
+        self.den == 1
+    }
+
+    pub fn to_point(&self) -> Option<Point> {
+        if !self.is_integral() {
+            return None;
+        }
+
+        Some(Point::new(
+            i32::try_from(self.x).ok()?,
+            i32::try_from(self.y).ok()?,
+        ))
+    }
+}
+
+impl From<Point> for RationalPoint {
+    fn from(p: Point) -> Self {
+        RationalPoint {
+            x: p.x as i128,
+            y: p.y as i128,
+            den: 1,
+        }
+    }
+}
+
+// a * x + b * y = c
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub struct Line {
+    pub a: i64,
+    pub b: i64,
+    pub c: i64,
+}
+
+impl Line {
+    pub const fn new(a: i64, b: i64, c: i64) -> Self {
+        Line { a, b, c }
+    }
+
+    pub fn through(p1: Point, p2: Point) -> Self {
+        let a = p2.y as i64 - p1.y as i64;
+        let b = p1.x as i64 - p2.x as i64;
+        Line::new(a, b, a * p1.x as i64 + b * p1.y as i64)
+    }
+
+    pub const fn horizontal(y: i64) -> Self {
+        Line::new(0, 1, y)
+    }
+
+    pub const fn vertical(x: i64) -> Self {
+        Line::new(1, 0, x)
+    }
+
+    // y - x = p.y - p.x
+    pub fn diagonal_up(p: Point) -> Self {
+        Line::new(-1, 1, p.y as i64 - p.x as i64)
+    }
+
+    // x + y = p.x + p.y
+    pub fn diagonal_down(p: Point) -> Self {
+        Line::new(1, 1, p.x as i64 + p.y as i64)
+    }
+
+    pub fn is_axis_aligned(&self) -> bool {
+        self.a == 0 || self.b == 0
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        self.a.abs() == self.b.abs() && self.a != 0
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        self.a * p.x as i64 + self.b * p.y as i64 == self.c
+    }
+
+    pub fn is_parallel(&self, other: &Line) -> bool {
+        self.a as i128 * other.b as i128 == self.b as i128 * other.a as i128
+    }
+
+    // parallel and coincident lines have no single intersection point
+    pub fn intersection(&self, other: &Line) -> Option<RationalPoint> {
+        let (a1, b1, c1) = (self.a as i128, self.b as i128, self.c as i128);
+        let (a2, b2, c2) = (other.a as i128, other.b as i128, other.c as i128);
+
+        let det = a1 * b2 - a2 * b1;
+        if det == 0 {
+            return None;
+        }
+
+        Some(RationalPoint::new(
+            c1 * b2 - c2 * b1,
+            a1 * c2 - a2 * c1,
+            det,
+        ))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum SegmentIntersection {
+    Point(RationalPoint),
+    Overlap(Segment),
+}
+
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub struct Segment {
+    pub p1: Point,
+    pub p2: Point,
+}
+
+impl Segment {
+    pub const fn new(p1: Point, p2: Point) -> Self {
+        Segment { p1, p2 }
+    }
+
+    pub fn line(&self) -> Line {
+        Line::through(self.p1, self.p2)
+    }
+
+    pub fn is_axis_aligned(&self) -> bool {
+        self.p1.x == self.p2.x || self.p1.y == self.p2.y
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        let d = self.p2 - self.p1;
+        d.x.abs() == d.y.abs()
+    }
+
+    fn contains_rational(&self, p: &RationalPoint) -> bool {
+        let in_range = |a: i32, b: i32, v: i128| {
+            let (lo, hi) = (i32::min(a, b) as i128, i32::max(a, b) as i128);
+            lo * p.den <= v && v <= hi * p.den
+        };
+
+        in_range(self.p1.x, self.p2.x, p.x) && in_range(self.p1.y, self.p2.y, p.y)
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        self.line().contains(p) && self.contains_rational(&RationalPoint::from(p))
+    }
+
+    pub fn intersection(&self, other: &Segment) -> Option<SegmentIntersection> {
+        // a single point has no line through it
+        if self.p1 == self.p2 {
+            return other
+                .contains(self.p1)
+                .then_some(SegmentIntersection::Point(RationalPoint::from(self.p1)));
+        }
+        if other.p1 == other.p2 {
+            return self
+                .contains(other.p1)
+                .then_some(SegmentIntersection::Point(RationalPoint::from(other.p1)));
+        }
+
+        let l1 = self.line();
+        let l2 = other.line();
+
+        if let Some(p) = l1.intersection(&l2) {
+            return (self.contains_rational(&p) && other.contains_rational(&p))
+                .then_some(SegmentIntersection::Point(p));
+        }
+
+        if !l1.contains(other.p1) {
+            return None;
+        }
+
+        // collinear, order both segments along the same direction and clip
+        let (s1, e1) = (Point::min(self.p1, self.p2), Point::max(self.p1, self.p2));
+        let (s2, e2) = (
+            Point::min(other.p1, other.p2),
+            Point::max(other.p1, other.p2),
+        );
+        let start = Point::max(s1, s2);
+        let end = Point::min(e1, e2);
+
+        match start.cmp(&end) {
+            std::cmp::Ordering::Greater => None,
+            std::cmp::Ordering::Equal => {
+                Some(SegmentIntersection::Point(RationalPoint::from(start)))
+            }
+            std::cmp::Ordering::Less => {
+                Some(SegmentIntersection::Overlap(Segment::new(start, end)))
+            }
+        }
+    }
+
+    // lattice points on an axis-aligned or diagonal segment
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let d = self.p2 - self.p1;
+        assert!(
+            self.is_axis_aligned() || self.is_diagonal(),
+            "segment is not axis-aligned or diagonal"
+        );
+
+        let step = d.signum();
+        let start = self.p1;
+        (0..=i32::max(d.x.abs(), d.y.abs())).map(move |i| start + step * i)
+    }
+}
+
+// all points within manhattan distance radius from center
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub struct Diamond {
+    pub center: Point,
+    pub radius: i32,
+}
+
+impl Diamond {
+    pub const fn new(center: Point, radius: i32) -> Self {
+        Diamond { center, radius }
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        self.center.manhattan(p) <= self.radius
+    }
+
+    pub fn grow(&self, n: i32) -> Self {
+        Diamond::new(self.center, self.radius + n)
+    }
+
+    pub fn corners(&self) -> [Point; 4] {
+        let Diamond { center, radius } = *self;
+        [
+            center + Point::new(0, -radius),
+            center + Point::new(radius, 0),
+            center + Point::new(0, radius),
+            center + Point::new(-radius, 0),
+        ]
+    }
+
+    pub fn edges(&self) -> [Segment; 4] {
+        let [top, right, bottom, left] = self.corners();
+        [
+            Segment::new(top, right),
+            Segment::new(right, bottom),
+            Segment::new(bottom, left),
+            Segment::new(left, top),
+        ]
+    }
+
+    pub fn row_span(&self, y: i32) -> Option<Interval> {
+        let diff = self.radius - (self.center.y - y).abs();
+        (diff >= 0).then(|| Interval::from(self.center.x - diff..=self.center.x + diff))
+    }
+
+    // points where the boundaries cross, shared boundary segments are reported by their ends
+    pub fn boundary_intersections(&self, other: &Diamond) -> Vec<RationalPoint> {
+        let mut result = vec![];
+        for e1 in self.edges() {
+            for e2 in other.edges() {
+                match e1.intersection(&e2) {
+                    Some(SegmentIntersection::Point(p)) => result.push(p),
+                    Some(SegmentIntersection::Overlap(s)) => {
+                        result.push(RationalPoint::from(s.p1));
+                        result.push(RationalPoint::from(s.p2));
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        result.sort_unstable_by_key(|p| (p.x, p.y, p.den));
+        result.dedup();
+        result
+    }
+}
+
+// twice the area of a simple polygon, vertices in either order
+pub fn shoelace_double_area(vertices: &[Point]) -> i64 {
+    let n = vertices.len();
+    let sum = (0..n)
+        .map(|i| {
+            let (a, b) = (vertices[i], vertices[(i + 1) % n]);
+            a.x as i64 * b.y as i64 - b.x as i64 * a.y as i64
+        })
+        .sum::<i64>();
+
+    sum.abs()
+}
+
+pub fn boundary_points(vertices: &[Point]) -> i64 {
+    let n = vertices.len();
+    (0..n)
+        .map(|i| {
+            let d = vertices[(i + 1) % n] - vertices[i];
+            gcd(d.x, d.y) as i64
+        })
+        .sum()
+}
+
+// Pick's theorem: A = I + B / 2 - 1
+pub fn interior_points(vertices: &[Point]) -> i64 {
+    (shoelace_double_area(vertices) - boundary_points(vertices) + 2) / 2
+}
+
+pub fn enclosed_points(vertices: &[Point]) -> i64 {
+    interior_points(vertices) + boundary_points(vertices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_intersection() {
+        let l1 = Line::through(Point::new(0, 0), Point::new(2, 1));
+        let l2 = Line::vertical(1);
+
+        assert_eq!(
+            l1.intersection(&l2),
+            Some(RationalPoint { x: 2, y: 1, den: 2 })
+        );
+        assert_eq!(
+            l1.intersection(&Line::through(Point::new(0, 1), Point::new(2, 2))),
+            None
+        );
+
+        let p = Line::diagonal_up(Point::new(0, 0))
+            .intersection(&Line::diagonal_down(Point::new(4, 0)))
+            .unwrap();
+        assert_eq!(p.to_point(), Some(Point::new(2, 2)));
+    }
+
+    #[test]
+    fn test_segment_intersection() {
+        let s1 = Segment::new(Point::new(0, 0), Point::new(4, 4));
+        let s2 = Segment::new(Point::new(0, 4), Point::new(1, 3));
+        let s3 = Segment::new(Point::new(6, 6), Point::new(2, 2));
+
+        assert_eq!(s1.intersection(&s2), None);
+        assert_eq!(
+            s1.intersection(&s3),
+            Some(SegmentIntersection::Overlap(Segment::new(
+                Point::new(2, 2),
+                Point::new(4, 4)
+            )))
+        );
+        assert_eq!(s3.points().count(), 5);
+
+        let point = Segment::new(Point::new(0, 5), Point::new(0, 5));
+        let s4 = Segment::new(Point::new(0, 0), Point::new(1, 0));
+        assert_eq!(point.intersection(&s4), None);
+        assert_eq!(s4.intersection(&point), None);
+        assert_eq!(
+            point.intersection(&Segment::new(Point::new(0, 0), Point::new(0, 9))),
+            Some(SegmentIntersection::Point(RationalPoint::new(0, 5, 1)))
+        );
+        assert_eq!(
+            point.intersection(&point),
+            Some(SegmentIntersection::Point(RationalPoint::new(0, 5, 1)))
+        );
+    }
+
+    #[test]
+    fn test_diamond() {
+        let d1 = Diamond::new(Point::new(0, 0), 2);
+        let d2 = Diamond::new(Point::new(3, 0), 2);
+
+        let points = d1
+            .boundary_intersections(&d2)
+            .iter()
+            .map(|p| (p.x, p.y, p.den))
+            .collect::<Vec<_>>();
+        assert_eq!(points, vec![(3, -1, 2), (3, 1, 2)]);
+        assert_eq!(d1.row_span(1), Some(Interval::new(-1, 1)));
+        assert_eq!(d1.row_span(3), None);
+
+        let single = Diamond::new(Point::new(0, 5), 0);
+        assert_eq!(single.boundary_intersections(&d1), vec![]);
+        assert_eq!(
+            single.boundary_intersections(&Diamond::new(Point::new(1, 1), 5)),
+            vec![RationalPoint::new(0, 5, 1)]
+        );
+    }
+
+    #[test]
+    fn test_large_values() {
+        let line = Line::through(Point::new(i32::MIN, 0), Point::new(i32::MAX, 1));
+        assert_eq!((line.a, line.b), (1, -(u32::MAX as i64)));
+        assert_eq!(
+            Line::diagonal_down(Point::new(i32::MAX, i32::MAX)).c,
+            2 * i32::MAX as i64
+        );
+
+        let p = Line::new(i64::MAX, 1, i64::MAX)
+            .intersection(&Line::new(1, i64::MAX, -i64::MAX))
+            .unwrap();
+        // x = (MAX^2 + MAX) / (MAX^2 - 1) = MAX / (MAX - 1)
+        assert_eq!((p.x, p.den), (i64::MAX as i128, i64::MAX as i128 - 1));
+        assert_eq!(p.to_point(), None);
+        assert_eq!(RationalPoint::new(-6, 4, -2), RationalPoint::new(3, -2, 1));
+    }
+
+    #[test]
+    fn test_polygon() {
+        let square = [
+            Point::new(0, 0),
+            Point::new(4, 0),
+            Point::new(4, 3),
+            Point::new(0, 3),
+        ];
+
+        assert_eq!(shoelace_double_area(&square), 24);
+        assert_eq!(boundary_points(&square), 14);
+        assert_eq!(interior_points(&square), 6);
+        assert_eq!(enclosed_points(&square), 20);
+    }
+}
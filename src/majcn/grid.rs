@@ -1,3 +1,5 @@
+use std::collections::VecDeque;
+
 pub struct ArenaTree<T> {
     arena: Vec<Node<T>>,
 }
@@ -6,6 +8,7 @@ struct Node<T> {
     value: T,
     parent: Option<usize>,
     children: Vec<usize>,
+    removed: bool,
 }
 
 impl<T> ArenaTree<T> {
@@ -15,17 +18,25 @@ impl<T> ArenaTree<T> {
                 value,
                 parent: None,
                 children: vec![],
+                removed: false,
             }],
         }
     }
 
+    pub fn root(&self) -> usize {
+        0
+    }
+
     pub fn insert(&mut self, node: usize, value: T) -> usize {
+        assert!(!self.arena[node].removed, "node was removed!");
+
         let new_node_idx = self.arena.len();
 
         let new_node = Node {
             value,
             parent: Some(node),
             children: vec![],
+            removed: false,
         };
 
         self.arena.push(new_node);
@@ -37,16 +48,129 @@ impl<T> ArenaTree<T> {
         self.arena[node].parent
     }
 
-    pub fn get_children(&self, node: usize) -> Vec<usize> {
-        self.arena[node].children.clone()
+    pub fn get_children(&self, node: usize) -> &[usize] {
+        &self.arena[node].children
     }
 
     pub fn has_children(&self, node: usize) -> bool {
         !self.arena[node].children.is_empty()
     }
 
+    pub fn is_removed(&self, node: usize) -> bool {
+        self.arena[node].removed
+    }
+
+    // number of nodes which were not removed
+    pub fn len(&self) -> usize {
+        self.arena.iter().filter(|x| !x.removed).count()
+    }
+
+    // the root can not be removed, so there is always at least one node
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = &T> {
-        self.arena.iter().map(|x| &x.value)
+        self.iter_with_keys().map(|(_, v)| v)
+    }
+
+    pub fn iter_with_keys(&self) -> impl Iterator<Item = (usize, &T)> {
+        self.arena
+            .iter()
+            .enumerate()
+            .filter(|(_, x)| !x.removed)
+            .map(|(i, x)| (i, &x.value))
+    }
+
+    pub fn ancestors(&self, node: usize) -> Ancestors<'_, T> {
+        Ancestors {
+            tree: self,
+            node: self.arena[node].parent,
+        }
+    }
+
+    pub fn depth(&self, node: usize) -> usize {
+        self.ancestors(node).count()
+    }
+
+    pub fn subtree_size(&self, node: usize) -> usize {
+        self.pre_order(node).count()
+    }
+
+    pub fn pre_order(&self, node: usize) -> PreOrder<'_, T> {
+        PreOrder {
+            tree: self,
+            stack: vec![node],
+        }
+    }
+
+    pub fn post_order(&self, node: usize) -> PostOrder<'_, T> {
+        PostOrder {
+            tree: self,
+            stack: vec![(node, false)],
+        }
+    }
+
+    pub fn bfs(&self, node: usize) -> Bfs<'_, T> {
+        Bfs {
+            tree: self,
+            queue: VecDeque::from([node]),
+        }
+    }
+
+    fn detach(&mut self, node: usize) {
+        if let Some(parent) = self.arena[node].parent.take() {
+            self.arena[parent].children.retain(|&x| x != node);
+        }
+    }
+
+    // removes the whole subtree, indexes of other nodes stay the same
+    pub fn remove(&mut self, node: usize) {
+        if node == self.root() {
+            panic!("root can not be removed!")
+        }
+
+        self.detach(node);
+
+        let subtree = self.pre_order(node).collect::<Vec<_>>();
+        for x in subtree {
+            self.arena[x].removed = true;
+        }
+    }
+
+    pub fn reparent(&mut self, node: usize, new_parent: usize) {
+        assert!(
+            !self.arena[node].removed && !self.arena[new_parent].removed,
+            "node was removed!"
+        );
+        if node == new_parent || self.ancestors(new_parent).any(|x| x == node) {
+            panic!("node can not be moved into its own subtree!")
+        }
+
+        self.detach(node);
+        self.arena[node].parent = Some(new_parent);
+        self.arena[new_parent].children.push(node);
+    }
+
+    // aggregates values bottom-up in a single pass, result is indexed by node
+    pub fn fold<A, F>(&self, node: usize, mut f: F) -> Vec<Option<A>>
+    where
+        F: FnMut(&T, FoldChildren<'_, A>) -> A,
+    {
+        let mut result = std::iter::repeat_with(|| None)
+            .take(self.arena.len())
+            .collect::<Vec<_>>();
+
+        for x in self.post_order(node) {
+            let children = FoldChildren {
+                values: &result,
+                children: self.arena[x].children.iter(),
+            };
+            let value = f(&self.arena[x].value, children);
+            result[x] = Some(value);
+        }
+
+        result
     }
 }
 
@@ -58,3 +182,346 @@ impl<T> std::ops::Index<usize> for ArenaTree<T> {
         &self.arena[index].value
     }
 }
+
+impl<T> std::ops::IndexMut<usize> for ArenaTree<T> {
+    #[inline]
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        assert!(!self.arena[index].removed, "node was removed!");
+        &mut self.arena[index].value
+    }
+}
+
+pub struct Ancestors<'a, T> {
+    tree: &'a ArenaTree<T>,
+    node: Option<usize>,
+}
+
+impl<T> Iterator for Ancestors<'_, T> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.node?;
+        self.node = self.tree.arena[result].parent;
+        Some(result)
+    }
+}
+
+pub struct PreOrder<'a, T> {
+    tree: &'a ArenaTree<T>,
+    stack: Vec<usize>,
+}
+
+impl<T> Iterator for PreOrder<'_, T> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.stack.pop()?;
+        self.stack
+            .extend(self.tree.arena[result].children.iter().rev());
+        Some(result)
+    }
+}
+
+pub struct PostOrder<'a, T> {
+    tree: &'a ArenaTree<T>,
+    stack: Vec<(usize, bool)>,
+}
+
+impl<T> Iterator for PostOrder<'_, T> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (node, children_visited) = self.stack.pop()?;
+            if children_visited {
+                return Some(node);
+            }
+
+            self.stack.push((node, true));
+            self.stack.extend(
+                self.tree.arena[node]
+                    .children
+                    .iter()
+                    .rev()
+                    .map(|&x| (x, false)),
+            );
+        }
+    }
+}
+
+pub struct Bfs<'a, T> {
+    tree: &'a ArenaTree<T>,
+    queue: VecDeque<usize>,
+}
+
+impl<T> Iterator for Bfs<'_, T> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.queue.pop_front()?;
+        self.queue.extend(self.tree.arena[result].children.iter());
+        Some(result)
+    }
+}
+
+pub struct FoldChildren<'a, A> {
+    values: &'a [Option<A>],
+    children: std::slice::Iter<'a, usize>,
+}
+
+impl<'a, A> Iterator for FoldChildren<'a, A> {
+    type Item = &'a A;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.children
+            .next()
+            .map(|&x| self.values[x].as_ref().unwrap())
+    }
+}
+
+pub struct NamedNode<T> {
+    pub name: String,
+    pub value: T,
+}
+
+// children are unique by name, so revisiting a path returns the same node
+pub struct PathTree<T> {
+    tree: ArenaTree<NamedNode<T>>,
+}
+
+impl<T> PathTree<T> {
+    pub fn new(value: T) -> Self {
+        PathTree {
+            tree: ArenaTree::new(NamedNode {
+                name: String::from("/"),
+                value,
+            }),
+        }
+    }
+
+    pub fn tree(&self) -> &ArenaTree<NamedNode<T>> {
+        &self.tree
+    }
+
+    pub fn root(&self) -> usize {
+        self.tree.root()
+    }
+
+    pub fn child(&self, node: usize, name: &str) -> Option<usize> {
+        self.tree
+            .get_children(node)
+            .iter()
+            .copied()
+            .find(|&x| self.tree[x].name == name)
+    }
+
+    pub fn get_or_insert_with<F>(&mut self, node: usize, name: &str, f: F) -> usize
+    where
+        F: FnOnce() -> T,
+    {
+        match self.child(node, name) {
+            Some(x) => x,
+            None => self.tree.insert(
+                node,
+                NamedNode {
+                    name: String::from(name),
+                    value: f(),
+                },
+            ),
+        }
+    }
+
+    // absolute paths start at root, ".." on root stays on root
+    pub fn resolve(&self, from: usize, path: &str) -> Option<usize> {
+        let mut node = if path.starts_with('/') {
+            self.root()
+        } else {
+            from
+        };
+
+        for component in path.split('/') {
+            node = match component {
+                "" | "." => node,
+                ".." => self.tree.get_parent(node).unwrap_or(node),
+                name => self.child(node, name)?,
+            };
+        }
+
+        Some(node)
+    }
+
+    pub fn path(&self, node: usize) -> String {
+        let mut names = std::iter::once(node)
+            .chain(self.tree.ancestors(node))
+            .filter(|&x| x != self.root())
+            .map(|x| self.tree[x].name.as_str())
+            .collect::<Vec<_>>();
+        names.reverse();
+
+        format!("/{}", names.join("/"))
+    }
+
+    pub fn render<F>(&self, label: F) -> String
+    where
+        F: Fn(&NamedNode<T>) -> String,
+    {
+        fn render_node<T, F>(
+            tree: &ArenaTree<NamedNode<T>>,
+            node: usize,
+            prefix: &str,
+            label: &F,
+            result: &mut String,
+        ) where
+            F: Fn(&NamedNode<T>) -> String,
+        {
+            let children = tree.get_children(node);
+            for (i, &child) in children.iter().enumerate() {
+                let last = i == children.len() - 1;
+                let (branch, indent) = if last {
+                    ("└── ", "    ")
+                } else {
+                    ("├── ", "│   ")
+                };
+
+                result.push_str(prefix);
+                result.push_str(branch);
+                result.push_str(&label(&tree[child]));
+                result.push('\n');
+
+                render_node(tree, child, &format!("{prefix}{indent}"), label, result);
+            }
+        }
+
+        let mut result = label(&self.tree[self.root()]);
+        result.push('\n');
+        render_node(&self.tree, self.root(), "", &label, &mut result);
+        result
+    }
+}
+
+impl<T> std::ops::Index<usize> for PathTree<T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.tree[index].value
+    }
+}
+
+impl<T> std::ops::IndexMut<usize> for PathTree<T> {
+    #[inline]
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.tree[index].value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //       0
+    //     /   \
+    //    1     2
+    //   / \     \
+    //  3   4     5
+    fn build() -> ArenaTree<u32> {
+        let mut tree = ArenaTree::new(0);
+        let a = tree.insert(0, 1);
+        let b = tree.insert(0, 2);
+        tree.insert(a, 3);
+        tree.insert(a, 4);
+        tree.insert(b, 5);
+        tree
+    }
+
+    #[test]
+    fn test_traversals() {
+        let tree = build();
+
+        assert_eq!(
+            tree.pre_order(0).collect::<Vec<_>>(),
+            vec![0, 1, 3, 4, 2, 5]
+        );
+        assert_eq!(
+            tree.post_order(0).collect::<Vec<_>>(),
+            vec![3, 4, 1, 5, 2, 0]
+        );
+        assert_eq!(tree.bfs(0).collect::<Vec<_>>(), vec![0, 1, 2, 3, 4, 5]);
+        assert_eq!(tree.ancestors(4).collect::<Vec<_>>(), vec![1, 0]);
+        assert_eq!(tree.depth(5), 2);
+        assert_eq!(tree.subtree_size(1), 3);
+
+        let sums = tree.fold(0, |v, children| v + children.sum::<u32>());
+        assert_eq!(sums[0], Some(15));
+        assert_eq!(sums[1], Some(8));
+    }
+
+    #[test]
+    fn test_restructure() {
+        let mut tree = build();
+
+        tree.reparent(1, 5);
+        assert_eq!(tree.get_children(0), &[2]);
+        assert_eq!(tree.ancestors(3).collect::<Vec<_>>(), vec![1, 5, 2, 0]);
+
+        tree.remove(5);
+        assert_eq!(tree.len(), 2);
+        assert!(tree.is_removed(3));
+        assert_eq!(tree.iter().copied().collect::<Vec<_>>(), vec![0, 2]);
+    }
+
+    #[test]
+    fn test_removed_nodes() {
+        let mut tree = build();
+        tree.remove(1);
+        tree[5] = 7;
+
+        assert!(!tree.is_empty());
+        assert_eq!(tree.iter().copied().collect::<Vec<_>>(), vec![0, 2, 7]);
+    }
+
+    #[test]
+    #[should_panic(expected = "node was removed!")]
+    fn test_insert_removed() {
+        let mut tree = build();
+        tree.remove(1);
+        tree.insert(3, 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "node was removed!")]
+    fn test_reparent_removed() {
+        let mut tree = build();
+        tree.remove(1);
+        tree.reparent(5, 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "node was removed!")]
+    fn test_index_removed() {
+        let mut tree = build();
+        tree.remove(1);
+        tree[3] = 7;
+    }
+
+    #[test]
+    fn test_path_tree() {
+        let mut tree = PathTree::new(0);
+        let a = tree.get_or_insert_with(tree.root(), "a", || 1);
+        let b = tree.get_or_insert_with(a, "b", || 2);
+        tree.get_or_insert_with(a, "c", || 3);
+        tree.get_or_insert_with(tree.root(), "d", || 4);
+
+        assert_eq!(tree.get_or_insert_with(tree.root(), "a", || 5), a);
+        assert_eq!(tree.resolve(b, "../c/.."), Some(a));
+        assert_eq!(tree.resolve(b, "/a/b"), Some(b));
+        assert_eq!(tree.resolve(b, "/x"), None);
+        assert_eq!(tree.path(b), "/a/b");
+
+        let rendered = tree.render(|n| format!("{} ({})", n.name, n.value));
+        assert_eq!(
+            rendered,
+            "/ (0)\n├── a (1)\n│   ├── b (2)\n│   └── c (3)\n└── d (4)\n"
+        );
+    }
+}
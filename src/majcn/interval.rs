@@ -0,0 +1,271 @@
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, PartialOrd, Ord)]
+pub struct Interval {
+    pub min: i64,
+    pub max: i64,
+}
+
+impl Interval {
+    pub const fn new(min: i64, max: i64) -> Self {
+        Interval { min, max }
+    }
+
+    // empty intervals have no length, the full i64 range saturates at u64::MAX
+    #[inline]
+    pub fn len(&self) -> u64 {
+        if self.is_empty() {
+            return 0;
+        }
+
+        self.max.abs_diff(self.min).saturating_add(1)
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.min > self.max
+    }
+
+    #[inline]
+    pub fn contains(&self, value: i64) -> bool {
+        self.min <= value && value <= self.max
+    }
+
+    #[inline]
+    pub fn contains_interval(&self, other: &Interval) -> bool {
+        self.min <= other.min && other.max <= self.max
+    }
+
+    #[inline]
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        self.min <= other.max && other.min <= self.max
+    }
+
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        let result = Interval::new(i64::max(self.min, other.min), i64::min(self.max, other.max));
+        (!result.is_empty()).then_some(result)
+    }
+}
+
+impl<T: Into<i64> + Copy> From<std::ops::RangeInclusive<T>> for Interval {
+    fn from(range: std::ops::RangeInclusive<T>) -> Self {
+        Interval::new((*range.start()).into(), (*range.end()).into())
+    }
+}
+
+// sorted, disjoint and non-adjacent inclusive intervals
+#[derive(Debug, Clone, Default, Hash, Eq, PartialEq)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        IntervalSet { intervals: vec![] }
+    }
+
+    pub fn insert(&mut self, interval: Interval) {
+        if interval.is_empty() {
+            return;
+        }
+
+        let lo = self
+            .intervals
+            .partition_point(|x| x.max.saturating_add(1) < interval.min);
+        let hi = self
+            .intervals
+            .partition_point(|x| x.min <= interval.max.saturating_add(1));
+
+        let merged = self.intervals[lo..hi].iter().fold(interval, |acc, x| {
+            Interval::new(i64::min(acc.min, x.min), i64::max(acc.max, x.max))
+        });
+
+        self.intervals.splice(lo..hi, [merged]);
+    }
+
+    pub fn remove(&mut self, interval: Interval) {
+        if interval.is_empty() {
+            return;
+        }
+
+        let lo = self.intervals.partition_point(|x| x.max < interval.min);
+        let hi = self.intervals.partition_point(|x| x.min <= interval.max);
+
+        let mut remaining = Vec::with_capacity(2);
+        if let Some(first) = self.intervals[lo..hi].first() {
+            if first.min < interval.min {
+                remaining.push(Interval::new(first.min, interval.min - 1));
+            }
+        }
+        if let Some(last) = self.intervals[lo..hi].last() {
+            if interval.max < last.max {
+                remaining.push(Interval::new(interval.max + 1, last.max));
+            }
+        }
+
+        self.intervals.splice(lo..hi, remaining);
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        let i = self.intervals.partition_point(|x| x.max < value);
+        self.intervals.get(i).is_some_and(|x| x.contains(value))
+    }
+
+    pub fn contains_interval(&self, interval: &Interval) -> bool {
+        let i = self.intervals.partition_point(|x| x.max < interval.min);
+        self.intervals
+            .get(i)
+            .is_some_and(|x| x.contains_interval(interval))
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        let mut result = self.clone();
+        for interval in other.iter() {
+            result.insert(*interval);
+        }
+        result
+    }
+
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let mut result = self.clone();
+        for interval in other.iter() {
+            result.remove(*interval);
+        }
+        result
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let mut intervals = vec![];
+
+        let mut left = self.intervals.iter().peekable();
+        let mut right = other.intervals.iter().peekable();
+        while let (Some(l), Some(r)) = (left.peek(), right.peek()) {
+            if let Some(x) = l.intersection(r) {
+                intervals.push(x);
+            }
+
+            if l.max < r.max {
+                left.next();
+            } else {
+                right.next();
+            }
+        }
+
+        IntervalSet { intervals }
+    }
+
+    // number of integers covered by the set
+    pub fn coverage(&self) -> u64 {
+        self.intervals
+            .iter()
+            .fold(0, |acc: u64, x| acc.saturating_add(x.len()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.intervals.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Interval> {
+        self.intervals.iter()
+    }
+
+    pub fn gaps(&self) -> impl Iterator<Item = Interval> + '_ {
+        self.intervals
+            .windows(2)
+            .map(|w| Interval::new(w[0].max + 1, w[1].min - 1))
+    }
+
+    pub fn gaps_within(&self, bounds: Interval) -> impl Iterator<Item = Interval> {
+        let mut result = IntervalSet::new();
+        result.insert(bounds);
+        result.difference(self).intervals.into_iter()
+    }
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Interval>>(iter: I) -> Self {
+        let mut result = IntervalSet::new();
+        for interval in iter {
+            result.insert(interval);
+        }
+        result
+    }
+}
+
+impl<'a> IntoIterator for &'a IntervalSet {
+    type Item = &'a Interval;
+    type IntoIter = std::slice::Iter<'a, Interval>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.intervals.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(intervals: &[(i64, i64)]) -> IntervalSet {
+        intervals
+            .iter()
+            .map(|&(min, max)| Interval::new(min, max))
+            .collect()
+    }
+
+    #[test]
+    fn test_insert_merges() {
+        let s = set(&[(10, 12), (1, 3), (5, 5), (4, 4), (20, 30), (11, 22)]);
+
+        assert_eq!(s, set(&[(1, 5), (10, 30)]));
+        assert_eq!(s.coverage(), 26);
+        assert!(s.contains(4));
+        assert!(!s.contains(7));
+        assert!(s.contains_interval(&Interval::new(12, 29)));
+        assert!(!s.contains_interval(&Interval::new(5, 10)));
+    }
+
+    #[test]
+    fn test_len() {
+        assert_eq!(Interval::new(3, 3).len(), 1);
+        assert_eq!(Interval::new(-5, 4).len(), 10);
+        assert_eq!(Interval::new(4, 3).len(), 0);
+        assert_eq!(Interval::new(10, -10).len(), 0);
+        assert_eq!(Interval::new(i64::MAX, i64::MIN).len(), 0);
+        assert_eq!(Interval::new(0, i64::MAX).len(), 1 << 63);
+        assert_eq!(Interval::new(i64::MIN, i64::MAX).len(), u64::MAX);
+
+        let s = set(&[(i64::MIN, -1), (1, i64::MAX)]);
+        assert_eq!(s.coverage(), u64::MAX);
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = set(&[(0, 10), (20, 30)]);
+        let b = set(&[(5, 25), (40, 50)]);
+
+        assert_eq!(a.union(&b), set(&[(0, 30), (40, 50)]));
+        assert_eq!(a.intersection(&b), set(&[(5, 10), (20, 25)]));
+        assert_eq!(a.difference(&b), set(&[(0, 4), (26, 30)]));
+        assert_eq!(b.difference(&a), set(&[(11, 19), (40, 50)]));
+    }
+
+    #[test]
+    fn test_gaps() {
+        let s = set(&[(0, 10), (13, 20), (30, 40)]);
+
+        assert_eq!(
+            s.gaps().collect::<Vec<_>>(),
+            vec![Interval::new(11, 12), Interval::new(21, 29)]
+        );
+        assert_eq!(
+            s.gaps_within(Interval::new(-5, 35)).collect::<Vec<_>>(),
+            vec![
+                Interval::new(-5, -1),
+                Interval::new(11, 12),
+                Interval::new(21, 29)
+            ]
+        );
+    }
+}
@@ -34,7 +34,7 @@ impl<T> Array2D<T> {
         self.line_size
     }
 
-    pub fn iter_keys(&self) -> Array2DIterKeys<T> {
+    pub fn iter_keys(&self) -> Array2DIterKeys<'_, T> {
         Array2DIterKeys { data: self, i: 0 }
     }
 }
@@ -48,6 +48,13 @@ impl<T> std::ops::Index<(usize, usize)> for Array2D<T> {
     }
 }
 
+impl<T> std::ops::IndexMut<(usize, usize)> for Array2D<T> {
+    #[inline]
+    fn index_mut(&mut self, index: (usize, usize)) -> &mut Self::Output {
+        &mut self.raw_data[index.1 * self.line_size + index.0]
+    }
+}
+
 pub struct Array2DIterKeys<'a, T> {
     data: &'a Array2D<T>,
     i: usize,
@@ -67,3 +74,383 @@ impl<T> Iterator for Array2DIterKeys<'_, T> {
         Some(result)
     }
 }
+
+struct CircularListNode<T> {
+    value: Option<T>,
+    prev: usize,
+    next: usize,
+}
+
+struct SkipPointers {
+    step: usize,
+    pointers: Vec<usize>,
+    valid: bool,
+}
+
+// handles returned by insert stay valid until the node is removed, also when it is moved
+pub struct CircularList<T> {
+    nodes: Vec<CircularListNode<T>>,
+    available_slots: Vec<usize>,
+    head: Option<usize>,
+    len: usize,
+    skip: Option<SkipPointers>,
+}
+
+impl<T> CircularList<T> {
+    pub fn new() -> Self {
+        CircularList {
+            nodes: vec![],
+            available_slots: vec![],
+            head: None,
+            len: 0,
+            skip: None,
+        }
+    }
+
+    // every node also remembers the node step positions ahead, walking k steps is O(k / step + step)
+    pub fn with_skip_step(mut self, step: usize) -> Self {
+        if step > 1 {
+            self.skip = Some(SkipPointers {
+                step,
+                pointers: vec![],
+                valid: false,
+            });
+        }
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn head(&self) -> Option<usize> {
+        self.head
+    }
+
+    pub fn get(&self, handle: usize) -> Option<&T> {
+        self.nodes.get(handle).and_then(|x| x.value.as_ref())
+    }
+
+    pub fn get_mut(&mut self, handle: usize) -> Option<&mut T> {
+        self.nodes.get_mut(handle).and_then(|x| x.value.as_mut())
+    }
+
+    pub fn next(&self, handle: usize) -> usize {
+        self.nodes[handle].next
+    }
+
+    pub fn prev(&self, handle: usize) -> usize {
+        self.nodes[handle].prev
+    }
+
+    pub fn push_back(&mut self, value: T) -> usize {
+        match self.head {
+            Some(head) => self.insert_after(self.nodes[head].prev, value),
+            None => {
+                let handle = self.allocate(value);
+                self.nodes[handle].prev = handle;
+                self.nodes[handle].next = handle;
+                self.head = Some(handle);
+                self.len = 1;
+                self.invalidate_skip();
+                handle
+            }
+        }
+    }
+
+    pub fn insert_after(&mut self, after: usize, value: T) -> usize {
+        let handle = self.allocate(value);
+        self.link_after(after, handle);
+        handle
+    }
+
+    pub fn insert_before(&mut self, before: usize, value: T) -> usize {
+        let after = self.nodes[before].prev;
+        self.insert_after(after, value)
+    }
+
+    pub fn remove(&mut self, handle: usize) -> T {
+        self.unlink(handle);
+        self.available_slots.push(handle);
+        self.nodes[handle].value.take().unwrap()
+    }
+
+    // negative k moves backwards
+    pub fn nth_from(&self, handle: usize, k: i64) -> usize {
+        let mut k = k.rem_euclid(self.len as i64) as usize;
+        let mut handle = handle;
+
+        if let Some(skip) = self.skip.as_ref().filter(|x| x.valid) {
+            while k >= skip.step {
+                handle = skip.pointers[handle];
+                k -= skip.step;
+            }
+        } else if k > self.len / 2 {
+            for _ in 0..self.len - k {
+                handle = self.nodes[handle].prev;
+            }
+            return handle;
+        }
+
+        for _ in 0..k {
+            handle = self.nodes[handle].next;
+        }
+        handle
+    }
+
+    // takes the node out and puts it back k positions further, k is reduced modulo len - 1
+    pub fn move_by(&mut self, handle: usize, k: i64) {
+        if self.len < 2 {
+            return;
+        }
+
+        let k = k.rem_euclid(self.len as i64 - 1);
+        if k == 0 {
+            return;
+        }
+
+        let prev = self.nodes[handle].prev;
+        self.unlink(handle);
+        self.rebuild_skip_if_needed();
+
+        let target = self.nth_from(prev, k);
+        self.link_after(target, handle);
+    }
+
+    pub fn iter(&self) -> CircularListIter<'_, T> {
+        CircularListIter {
+            list: self,
+            handle: self.head.unwrap_or_default(),
+            remaining: self.len,
+        }
+    }
+
+    pub fn iter_from(&self, handle: usize) -> CircularListIter<'_, T> {
+        CircularListIter {
+            list: self,
+            handle,
+            remaining: self.len,
+        }
+    }
+
+    fn allocate(&mut self, value: T) -> usize {
+        let node = CircularListNode {
+            value: Some(value),
+            prev: 0,
+            next: 0,
+        };
+
+        match self.available_slots.pop() {
+            Some(slot) => {
+                self.nodes[slot] = node;
+                slot
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn link_after(&mut self, after: usize, handle: usize) {
+        let next = self.nodes[after].next;
+        self.nodes[handle].prev = after;
+        self.nodes[handle].next = next;
+        self.nodes[after].next = handle;
+        self.nodes[next].prev = handle;
+        self.len += 1;
+
+        if !self.skip_can_update() {
+            self.invalidate_skip();
+            return;
+        }
+
+        // the step predecessors now reach one node less far
+        let skip = self.skip.as_mut().unwrap();
+        skip.pointers.resize(self.nodes.len(), 0);
+
+        let mut p = after;
+        for _ in 0..skip.step {
+            skip.pointers[p] = self.nodes[skip.pointers[p]].prev;
+            p = self.nodes[p].prev;
+        }
+        skip.pointers[handle] = self.nodes[skip.pointers[after]].next;
+    }
+
+    fn unlink(&mut self, handle: usize) {
+        if self.skip_can_update() {
+            // the step predecessors now reach one node further
+            let skip = self.skip.as_mut().unwrap();
+            let mut p = self.nodes[handle].prev;
+            for _ in 0..skip.step {
+                skip.pointers[p] = self.nodes[skip.pointers[p]].next;
+                p = self.nodes[p].prev;
+            }
+        } else {
+            self.invalidate_skip();
+        }
+
+        let CircularListNode { prev, next, .. } = self.nodes[handle];
+        self.nodes[prev].next = next;
+        self.nodes[next].prev = prev;
+        self.len -= 1;
+
+        if self.head == Some(handle) {
+            self.head = (self.len > 0).then_some(next);
+        }
+    }
+
+    fn skip_can_update(&self) -> bool {
+        self.skip
+            .as_ref()
+            .is_some_and(|x| x.valid && self.len > 2 * x.step + 2)
+    }
+
+    fn invalidate_skip(&mut self) {
+        if let Some(skip) = self.skip.as_mut() {
+            skip.valid = false;
+        }
+    }
+
+    fn rebuild_skip_if_needed(&mut self) {
+        let Some(skip) = self.skip.as_ref() else {
+            return;
+        };
+
+        if skip.valid || self.len <= 2 * skip.step + 2 {
+            return;
+        }
+
+        let step = skip.step;
+        let mut pointers = vec![0; self.nodes.len()];
+
+        let start = self.head.unwrap();
+        let mut ahead = start;
+        for _ in 0..step {
+            ahead = self.nodes[ahead].next;
+        }
+
+        let mut handle = start;
+        for _ in 0..self.len {
+            pointers[handle] = ahead;
+            handle = self.nodes[handle].next;
+            ahead = self.nodes[ahead].next;
+        }
+
+        self.skip = Some(SkipPointers {
+            step,
+            pointers,
+            valid: true,
+        });
+    }
+}
+
+impl<T> Default for CircularList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FromIterator<T> for CircularList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut result = CircularList::new();
+        for value in iter {
+            result.push_back(value);
+        }
+        result
+    }
+}
+
+impl<T> std::ops::Index<usize> for CircularList<T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).unwrap()
+    }
+}
+
+impl<T> std::ops::IndexMut<usize> for CircularList<T> {
+    #[inline]
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.get_mut(index).unwrap()
+    }
+}
+
+pub struct CircularListIter<'a, T> {
+    list: &'a CircularList<T>,
+    handle: usize,
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for CircularListIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let result = &self.list[self.handle];
+        self.handle = self.list.nodes[self.handle].next;
+        self.remaining -= 1;
+
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mix(data: &[i64], skip_step: usize) -> Vec<i64> {
+        let mut list = data
+            .iter()
+            .copied()
+            .collect::<CircularList<_>>()
+            .with_skip_step(skip_step);
+
+        for (handle, value) in data.iter().enumerate() {
+            list.move_by(handle, *value);
+        }
+
+        let zero = data.iter().position(|x| x == &0).unwrap();
+        list.iter_from(zero).copied().collect()
+    }
+
+    #[test]
+    fn test_move_by() {
+        let data = [1, 2, -3, 3, -2, 0, 4];
+
+        assert_eq!(mix(&data, 0), vec![0, 3, -2, 1, 2, -3, 4]);
+    }
+
+    #[test]
+    fn test_skip_pointers() {
+        let data = (0..200)
+            .map(|i: i64| (i * 7919 + 13) % 401 - 200)
+            .chain([0])
+            .collect::<Vec<_>>();
+
+        assert_eq!(mix(&data, 0), mix(&data, 5));
+        assert_eq!(mix(&data, 0), mix(&data, 14));
+    }
+
+    #[test]
+    fn test_insert_remove() {
+        let mut list = CircularList::new();
+        let a = list.push_back('a');
+        let c = list.push_back('c');
+        list.insert_before(c, 'b');
+        list.insert_after(c, 'd');
+
+        assert_eq!(list.iter().collect::<String>(), "abcd");
+        assert_eq!(list.remove(a), 'a');
+        assert_eq!(list.iter().collect::<String>(), "bcd");
+        assert_eq!(list[list.nth_from(c, -2)], 'd');
+    }
+}
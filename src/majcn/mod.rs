@@ -1,4 +1,23 @@
+pub mod automaton;
+pub mod base;
+pub mod branch_bound;
+pub mod cube;
+pub mod cycle;
+pub mod expr;
+pub mod geometry;
 pub mod grid;
+pub mod interval;
 pub mod list;
+pub mod monkey;
+pub mod num;
+pub mod ocr;
+pub mod packet;
 pub mod parse;
 pub mod point;
+pub mod point3;
+pub mod rope;
+pub mod sand;
+pub mod tetris;
+pub mod timegrid;
+pub mod treap;
+pub mod vm;
@@ -0,0 +1,423 @@
+use std::hash::Hash;
+
+use crate::majcn::cycle::CycleDetector;
+use crate::majcn::expr::Op;
+use crate::majcn::num::{checked_lcm_all, BigUint};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand {
+    Old,
+    Const(u64),
+}
+
+impl std::str::FromStr for Operand {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "old" => Ok(Operand::Old),
+            _ => s
+                .parse()
+                .map(Operand::Const)
+                .map_err(|_| format!("unknown operand: {s}")),
+        }
+    }
+}
+
+// new = left op right
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Operation {
+    pub left: Operand,
+    pub op: Op,
+    pub right: Operand,
+}
+
+impl Operation {
+    pub fn evaluate<R: WorryRule>(&self, rule: &R, old: &R::Level) -> Result<R::Level, String> {
+        let operand = |x: Operand| match x {
+            Operand::Old => old.clone(),
+            Operand::Const(v) => rule.level(v),
+        };
+
+        rule.apply(self.op, &operand(self.left), &operand(self.right))
+    }
+}
+
+// "old * 19", optionally prefixed with "new = "
+impl std::str::FromStr for Operation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let s = s.strip_prefix("new = ").unwrap_or(s);
+
+        let [left, op, right] = s.split_whitespace().collect::<Vec<_>>()[..] else {
+            return Err(format!("invalid operation: {s}"));
+        };
+
+        let mut op_chars = op.chars();
+        let op = match (op_chars.next(), op_chars.next()) {
+            (Some(c), None) => Op::try_from(c)?,
+            _ => return Err(format!("unknown operator: {op}")),
+        };
+
+        Ok(Operation {
+            left: left.parse()?,
+            op,
+            right: right.parse()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Monkey {
+    pub items: Vec<u64>,
+    pub operation: Operation,
+    pub divisor: u64,
+    pub if_true: usize,
+    pub if_false: usize,
+}
+
+pub trait WorryRule {
+    type Level: Clone + Hash + Eq;
+
+    fn level(&self, value: u64) -> Self::Level;
+
+    fn apply(&self, op: Op, left: &Self::Level, right: &Self::Level)
+        -> Result<Self::Level, String>;
+
+    // after the monkey gets bored with the item
+    fn relieve(&self, level: Self::Level) -> Result<Self::Level, String>;
+
+    fn is_divisible(&self, level: &Self::Level, divisor: u64) -> bool;
+}
+
+// exact levels divided by a constant after every inspection
+pub struct Divide(pub u64);
+
+impl WorryRule for Divide {
+    type Level = u64;
+
+    fn level(&self, value: u64) -> u64 {
+        value
+    }
+
+    fn apply(&self, op: Op, left: &u64, right: &u64) -> Result<u64, String> {
+        let result = match op {
+            Op::Add => left.checked_add(*right),
+            Op::Sub => left.checked_sub(*right),
+            Op::Mul => left.checked_mul(*right),
+            Op::Div => left.checked_div(*right),
+        };
+
+        result.ok_or_else(|| format!("worry level out of range: {left} {op:?} {right}"))
+    }
+
+    fn relieve(&self, level: u64) -> Result<u64, String> {
+        self.apply(Op::Div, &level, &self.0)
+    }
+
+    fn is_divisible(&self, level: &u64, divisor: u64) -> bool {
+        level.is_multiple_of(divisor)
+    }
+}
+
+// levels kept modulo the lcm of all divisors, which keeps every test intact
+pub struct ModuloLcm(u64);
+
+impl ModuloLcm {
+    pub fn new(monkeys: &[Monkey]) -> Result<Self, String> {
+        match checked_lcm_all(monkeys.iter().map(|x| x.divisor)) {
+            Some(0) => Err(String::from("divisibility by zero")),
+            Some(modulus) => Ok(ModuloLcm(modulus)),
+            None => Err(String::from("lcm of all divisors does not fit into u64")),
+        }
+    }
+
+    pub fn modulus(&self) -> u64 {
+        self.0
+    }
+}
+
+impl WorryRule for ModuloLcm {
+    type Level = u64;
+
+    fn level(&self, value: u64) -> u64 {
+        value % self.0
+    }
+
+    fn apply(&self, op: Op, left: &u64, right: &u64) -> Result<u64, String> {
+        let (a, b, m) = (*left as u128, *right as u128, self.0 as u128);
+        let result = match op {
+            Op::Add => (a + b) % m,
+            Op::Sub => (a + m - b) % m,
+            Op::Mul => a * b % m,
+            Op::Div => return Err(String::from("division does not work with modulo levels")),
+        };
+
+        Ok(result as u64)
+    }
+
+    fn relieve(&self, level: u64) -> Result<u64, String> {
+        Ok(level)
+    }
+
+    fn is_divisible(&self, level: &u64, divisor: u64) -> bool {
+        level.is_multiple_of(divisor)
+    }
+}
+
+// exact levels without any relief, they grow quickly
+pub struct Unbounded;
+
+impl WorryRule for Unbounded {
+    type Level = BigUint;
+
+    fn level(&self, value: u64) -> BigUint {
+        BigUint::from(value)
+    }
+
+    fn apply(&self, op: Op, left: &BigUint, right: &BigUint) -> Result<BigUint, String> {
+        match op {
+            Op::Add => Ok(left + right),
+            Op::Sub => left
+                .checked_sub(right)
+                .ok_or_else(|| String::from("negative worry level")),
+            Op::Mul => Ok(left * right),
+            Op::Div => left
+                .div_rem(right)
+                .map(|x| x.0)
+                .ok_or_else(|| String::from("division by zero")),
+        }
+    }
+
+    fn relieve(&self, level: BigUint) -> Result<BigUint, String> {
+        Ok(level)
+    }
+
+    fn is_divisible(&self, level: &BigUint, divisor: u64) -> bool {
+        level.rem_u64(divisor) == 0
+    }
+}
+
+pub struct Troop<R> {
+    monkeys: Vec<Monkey>,
+    rule: R,
+}
+
+impl<R: WorryRule> Troop<R> {
+    pub fn new(monkeys: Vec<Monkey>, rule: R) -> Result<Self, String> {
+        for (i, monkey) in monkeys.iter().enumerate() {
+            if monkey.divisor == 0 {
+                return Err(format!("monkey {i} tests divisibility by zero"));
+            }
+            for target in [monkey.if_true, monkey.if_false] {
+                if target >= monkeys.len() || target == i {
+                    return Err(format!("monkey {i} throws to invalid monkey {target}"));
+                }
+            }
+        }
+
+        Ok(Troop { monkeys, rule })
+    }
+
+    pub fn monkeys(&self) -> &[Monkey] {
+        &self.monkeys
+    }
+
+    // items move independently, an item thrown to a later monkey is inspected
+    // again in the same round
+    fn round(
+        &self,
+        monkey: usize,
+        level: R::Level,
+    ) -> Result<(usize, R::Level, Vec<usize>), String> {
+        let mut monkey = monkey;
+        let mut level = level;
+        let mut inspected = vec![];
+
+        loop {
+            let m = &self.monkeys[monkey];
+            inspected.push(monkey);
+
+            level = self
+                .rule
+                .relieve(m.operation.evaluate(&self.rule, &level)?)?;
+            let target = if self.rule.is_divisible(&level, m.divisor) {
+                m.if_true
+            } else {
+                m.if_false
+            };
+
+            let next_round = target < monkey;
+            monkey = target;
+            if next_round {
+                return Ok((monkey, level, inspected));
+            }
+        }
+    }
+
+    fn items(&self) -> Vec<(usize, R::Level)> {
+        let mut result = vec![];
+        for (i, monkey) in self.monkeys.iter().enumerate() {
+            result.extend(monkey.items.iter().map(|&x| (i, self.rule.level(x))));
+        }
+        result
+    }
+
+    // history[r][m] is the number of inspections by monkey m in the first r rounds
+    pub fn inspection_history(&self, rounds: usize) -> Result<Vec<Vec<u64>>, String> {
+        let mut result = vec![vec![0; self.monkeys.len()]; rounds + 1];
+
+        for (mut monkey, mut level) in self.items() {
+            for counts in result.iter_mut().skip(1) {
+                let inspected;
+                (monkey, level, inspected) = self.round(monkey, level)?;
+                for x in inspected {
+                    counts[x] += 1;
+                }
+            }
+        }
+
+        for r in 1..result.len() {
+            let (done, rest) = result.split_at_mut(r);
+            for (count, previous) in rest[0].iter_mut().zip(done[r - 1].iter()) {
+                *count += previous;
+            }
+        }
+
+        Ok(result)
+    }
+
+    // inspections per monkey, every item skips ahead once its route repeats
+    pub fn inspections(&self, rounds: usize) -> Result<Vec<u64>, String> {
+        let mut result = vec![0; self.monkeys.len()];
+
+        'items: for (mut monkey, mut level) in self.items() {
+            let mut detector = CycleDetector::new();
+            detector.observe(0, (monkey, level.clone()));
+
+            let mut history = vec![vec![0; self.monkeys.len()]];
+            for step in 1..=rounds {
+                let inspected;
+                (monkey, level, inspected) = self.round(monkey, level)?;
+
+                let mut counts = history[step - 1].clone();
+                for x in inspected {
+                    counts[x] += 1;
+                }
+                history.push(counts);
+
+                if let Some(cycle) = detector.observe(step, (monkey, level.clone())) {
+                    for (m, total) in result.iter_mut().enumerate() {
+                        let column = history.iter().map(|x| x[m]).collect::<Vec<u64>>();
+                        *total += cycle.extrapolate(&column, rounds);
+                    }
+                    continue 'items;
+                }
+            }
+
+            for (total, count) in result.iter_mut().zip(history[rounds].iter()) {
+                *total += count;
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+// product of the two highest inspection counts
+pub fn monkey_business(inspections: &[u64]) -> u64 {
+    let mut sorted = inspections.to_vec();
+    sorted.sort_unstable();
+    sorted.into_iter().rev().take(2).product()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monkey(items: &[u64], operation: &str, divisor: u64, targets: (usize, usize)) -> Monkey {
+        Monkey {
+            items: items.to_vec(),
+            operation: operation.parse().unwrap(),
+            divisor,
+            if_true: targets.0,
+            if_false: targets.1,
+        }
+    }
+
+    fn example() -> Vec<Monkey> {
+        vec![
+            monkey(&[79, 98], "old * 19", 23, (2, 3)),
+            monkey(&[54, 65, 75, 74], "old + 6", 19, (2, 0)),
+            monkey(&[79, 60, 97], "old * old", 13, (1, 3)),
+            monkey(&[74], "old + 3", 17, (0, 1)),
+        ]
+    }
+
+    #[test]
+    fn test_operation() {
+        let op = "new = old * old".parse::<Operation>().unwrap();
+        assert_eq!(op.evaluate(&Divide(3), &7), Ok(49));
+
+        let op = "100 - old".parse::<Operation>().unwrap();
+        assert_eq!(op.right, Operand::Old);
+        assert_eq!(op.evaluate(&Divide(3), &30), Ok(70));
+        assert!(op.evaluate(&Divide(3), &300).is_err());
+        assert_eq!(op.evaluate(&ModuloLcm(7), &3), Ok(6));
+
+        assert!("old ** 2".parse::<Operation>().is_err());
+        assert!("old * x".parse::<Operation>().is_err());
+        assert!("old *".parse::<Operation>().is_err());
+    }
+
+    #[test]
+    fn test_example() {
+        let troop = Troop::new(example(), Divide(3)).unwrap();
+        let history = troop.inspection_history(20).unwrap();
+        assert_eq!(history[20], vec![101, 95, 7, 105]);
+        assert_eq!(monkey_business(&history[20]), 10605);
+        assert_eq!(troop.inspections(20).unwrap(), history[20]);
+
+        let troop = Troop::new(example(), ModuloLcm::new(&example()).unwrap()).unwrap();
+        let history = troop.inspection_history(1000).unwrap();
+        assert_eq!(history[1], vec![2, 4, 3, 6]);
+        assert_eq!(history[20], vec![99, 97, 8, 103]);
+        assert_eq!(history[1000], vec![5204, 4792, 199, 5192]);
+        assert_eq!(troop.inspections(1000).unwrap(), history[1000]);
+        assert_eq!(
+            troop.inspections(10000).unwrap(),
+            vec![52166, 47830, 1938, 52013]
+        );
+    }
+
+    #[test]
+    fn test_rules() {
+        let exact = Troop::new(example(), Unbounded).unwrap();
+        let modulo = Troop::new(example(), ModuloLcm::new(&example()).unwrap()).unwrap();
+        assert_eq!(
+            exact.inspection_history(10).unwrap(),
+            modulo.inspection_history(10).unwrap()
+        );
+
+        let mut monkeys = example();
+        monkeys[1].operation = "old / 2".parse().unwrap();
+        let troop = Troop::new(monkeys, ModuloLcm::new(&example()).unwrap()).unwrap();
+        assert!(troop.inspections(1).is_err());
+
+        let troop = Troop::new(example(), Divide(1)).unwrap();
+        assert!(troop.inspections(20).is_err());
+
+        let mut monkeys = example();
+        monkeys[3].if_false = 3;
+        assert!(Troop::new(monkeys, Divide(3)).is_err());
+
+        let mut monkeys = example();
+        monkeys[0].divisor = 0;
+        assert!(ModuloLcm::new(&monkeys).is_err());
+        monkeys[0].divisor = 1 << 40;
+        monkeys[1].divisor = (1 << 40) - 1;
+        assert!(ModuloLcm::new(&monkeys).is_err());
+    }
+}
@@ -0,0 +1,652 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::ops::{Div, Mul, Rem, Sub};
+
+pub trait Integer:
+    Copy
+    + PartialEq
+    + PartialOrd
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    // unsigned type of the same width, holds the magnitude of every value
+    type Unsigned: Integer<Unsigned = Self::Unsigned>;
+
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn magnitude(self) -> Self::Unsigned;
+    fn from_magnitude(value: Self::Unsigned) -> Option<Self>;
+    fn checked_product(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty => $u:ty),*) => {
+        $(
+            impl Integer for $t {
+                type Unsigned = $u;
+
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                #[inline]
+                fn magnitude(self) -> $u {
+                    self.unsigned_abs()
+                }
+
+                #[inline]
+                fn from_magnitude(value: $u) -> Option<Self> {
+                    Self::try_from(value).ok()
+                }
+
+                #[inline]
+                fn checked_product(self, other: Self) -> Option<Self> {
+                    self.checked_mul(other)
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(i32 => u32, i64 => u64, i128 => u128);
+
+macro_rules! impl_unsigned_integer {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                type Unsigned = $t;
+
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                #[inline]
+                fn magnitude(self) -> Self {
+                    self
+                }
+
+                #[inline]
+                fn from_magnitude(value: Self) -> Option<Self> {
+                    Some(value)
+                }
+
+                #[inline]
+                fn checked_product(self, other: Self) -> Option<Self> {
+                    self.checked_mul(other)
+                }
+            }
+        )*
+    };
+}
+
+impl_unsigned_integer!(u32, u64, u128, usize);
+
+// works on magnitudes, so gcd(i64::MIN, 0) = 2^63 still fits
+pub fn gcd<T: Integer>(a: T, b: T) -> T::Unsigned {
+    let (mut a, mut b) = (a.magnitude(), b.magnitude());
+    while b != T::Unsigned::ZERO {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+// non-negative, None if it does not fit into T
+pub fn checked_lcm<T: Integer>(a: T, b: T) -> Option<T> {
+    if a == T::ZERO || b == T::ZERO {
+        return Some(T::ZERO);
+    }
+
+    let result = (a.magnitude() / gcd(a, b)).checked_product(b.magnitude())?;
+    T::from_magnitude(result)
+}
+
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    checked_lcm(a, b).expect("lcm overflow!")
+}
+
+pub fn gcd_all<T: Integer>(values: impl IntoIterator<Item = T>) -> T::Unsigned {
+    values
+        .into_iter()
+        .fold(T::Unsigned::ZERO, |acc, x| gcd(acc, x.magnitude()))
+}
+
+pub fn checked_lcm_all<T: Integer>(values: impl IntoIterator<Item = T>) -> Option<T> {
+    values.into_iter().try_fold(T::ONE, checked_lcm)
+}
+
+pub fn lcm_all<T: Integer>(values: impl IntoIterator<Item = T>) -> T {
+    checked_lcm_all(values).expect("lcm overflow!")
+}
+
+#[inline]
+pub fn mod_mul(a: u64, b: u64, m: u64) -> u64 {
+    (a as u128 * b as u128 % m as u128) as u64
+}
+
+pub fn mod_pow(base: u64, exp: u64, m: u64) -> u64 {
+    let mut result = 1 % m;
+    let mut base = base % m;
+    let mut exp = exp;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mod_mul(result, base, m);
+        }
+        base = mod_mul(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
+
+// (g, x, y) such that a * x + b * y = g = gcd(a, b)
+pub fn ext_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a.abs(), a.signum(), 0)
+    } else {
+        let (g, x, y) = ext_gcd(b, a % b);
+        (g, y, x - a / b * y)
+    }
+}
+
+pub fn mod_inverse(a: i64, m: u64) -> Option<u64> {
+    let (g, x, _) = ext_gcd(a as i128, m as i128);
+    (g == 1).then(|| x.rem_euclid(m as i128) as u64)
+}
+
+// solves x = r (mod m) for all pairs, moduli don't have to be coprime
+pub fn crt(congruences: &[(i64, u64)]) -> Option<(u64, u64)> {
+    let mut result: (i128, i128) = (0, 1);
+
+    for &(r, m) in congruences {
+        if m == 0 {
+            return None;
+        }
+
+        let (r, m) = (r as i128, m as i128);
+        let (g, p, _) = ext_gcd(result.1, m);
+
+        let diff = r - result.0;
+        if diff % g != 0 {
+            return None;
+        }
+
+        let modulus = result.1 / g * m;
+        if modulus > u64::MAX as i128 {
+            return None;
+        }
+
+        let t = (diff / g % (m / g) * p).rem_euclid(m / g);
+        result = ((result.0 + result.1 * t).rem_euclid(modulus), modulus);
+    }
+
+    Some((result.0 as u64, result.1 as u64))
+}
+
+// always normalized, den is positive, every operation is overflow checked
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub struct Rational {
+    pub num: i128,
+    pub den: i128,
+}
+
+impl Rational {
+    pub fn new(num: i128, den: i128) -> Self {
+        assert_ne!(den, 0, "division by zero!");
+        Rational::checked_new(num, den).expect("rational out of range!")
+    }
+
+    // None for a zero den or when the normalized value does not fit, like -1/i128::MIN
+    pub fn checked_new(num: i128, den: i128) -> Option<Self> {
+        if den == 0 {
+            return None;
+        }
+        if num == 0 {
+            return Some(Rational::integer(0));
+        }
+
+        // gcd does not fit into i128 only when both are i128::MIN
+        let d = gcd(num.unsigned_abs(), den.unsigned_abs());
+        let (num, den) = match i128::try_from(d) {
+            Ok(d) => (num / d, den / d),
+            Err(_) => (1, 1),
+        };
+
+        if den < 0 {
+            Some(Rational {
+                num: num.checked_neg()?,
+                den: den.checked_neg()?,
+            })
+        } else {
+            Some(Rational { num, den })
+        }
+    }
+
+    pub const fn integer(value: i128) -> Self {
+        Rational { num: value, den: 1 }
+    }
+
+    #[inline]
+    pub fn is_integer(&self) -> bool {
+        self.den == 1
+    }
+
+    pub fn to_integer(&self) -> Option<i128> {
+        self.is_integer().then_some(self.num)
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        let d = gcd(self.den, other.den) as i128;
+        let num = (self.num.checked_mul(other.den / d)?)
+            .checked_add(other.num.checked_mul(self.den / d)?)?;
+        Rational::checked_new(num, (self.den / d).checked_mul(other.den)?)
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.checked_add(Rational::checked_new(other.num.checked_neg()?, other.den)?)
+    }
+
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        // dens are positive so both gcds fit, even for a num of i128::MIN
+        let d1 = gcd(self.num.unsigned_abs(), other.den.unsigned_abs()) as i128;
+        let d2 = gcd(other.num.unsigned_abs(), self.den.unsigned_abs()) as i128;
+        Rational::checked_new(
+            (self.num / d1).checked_mul(other.num / d2)?,
+            (self.den / d2).checked_mul(other.den / d1)?,
+        )
+    }
+
+    pub fn checked_div(self, other: Self) -> Option<Self> {
+        if other.num == 0 {
+            return None;
+        }
+
+        self.checked_mul(Rational::checked_new(other.den, other.num)?)
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(value: i64) -> Self {
+        Rational::integer(value as i128)
+    }
+}
+
+impl std::ops::Add for Rational {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        self.checked_add(other).expect("overflow!")
+    }
+}
+
+impl std::ops::Sub for Rational {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        self.checked_sub(other).expect("overflow!")
+    }
+}
+
+impl std::ops::Mul for Rational {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self::Output {
+        self.checked_mul(other).expect("overflow!")
+    }
+}
+
+impl std::ops::Div for Rational {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self::Output {
+        assert_ne!(other.num, 0, "division by zero!");
+        self.checked_div(other).expect("overflow!")
+    }
+}
+
+// a/b against c/d with positive b and d, compares integer parts and then the
+// reciprocals of the remainders, so nothing can overflow
+fn cmp_fractions(a: i128, b: i128, c: i128, d: i128) -> Ordering {
+    let (q1, r1) = (a.div_euclid(b), a.rem_euclid(b));
+    let (q2, r2) = (c.div_euclid(d), c.rem_euclid(d));
+
+    match (q1.cmp(&q2), r1, r2) {
+        (Ordering::Equal, 0, 0) => Ordering::Equal,
+        (Ordering::Equal, 0, _) => Ordering::Less,
+        (Ordering::Equal, _, 0) => Ordering::Greater,
+        (Ordering::Equal, _, _) => cmp_fractions(d, r2, b, r1),
+        (ordering, _, _) => ordering,
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (
+            self.num.checked_mul(other.den),
+            other.num.checked_mul(self.den),
+        ) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            _ => cmp_fractions(self.num, self.den, other.num, other.den),
+        }
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+// arbitrarily large non-negative integer, u32 limbs with the lowest first
+// and without leading zero limbs
+#[derive(Debug, Clone, Default, Hash, Eq, PartialEq)]
+pub struct BigUint {
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn zero() -> Self {
+        BigUint { limbs: vec![] }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    fn normalized(mut self) -> Self {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+        self
+    }
+
+    pub fn bits(&self) -> u64 {
+        self.limbs.last().map_or(0, |x| {
+            32 * (self.limbs.len() as u64 - 1) + (32 - x.leading_zeros() as u64)
+        })
+    }
+
+    fn bit(&self, i: u64) -> bool {
+        self.limbs[(i / 32) as usize] & (1 << (i % 32)) != 0
+    }
+
+    // self * 2 + bit
+    fn shift_in(&mut self, bit: bool) {
+        let mut carry = bit as u32;
+        for limb in self.limbs.iter_mut() {
+            let next = *limb >> 31;
+            *limb = (*limb << 1) | carry;
+            carry = next;
+        }
+        if carry != 0 {
+            self.limbs.push(carry);
+        }
+    }
+
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.limbs[..] {
+            [] => Some(0),
+            [a] => Some(a as u64),
+            [a, b] => Some(a as u64 | (b as u64) << 32),
+            _ => None,
+        }
+    }
+
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        if self < other {
+            return None;
+        }
+
+        let mut limbs = Vec::with_capacity(self.limbs.len());
+        let mut borrow = 0;
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let b = other.limbs.get(i).copied().unwrap_or(0) as i64;
+            let mut v = a as i64 - b - borrow;
+            borrow = (v < 0) as i64;
+            if v < 0 {
+                v += 1 << 32;
+            }
+            limbs.push(v as u32);
+        }
+
+        Some(BigUint { limbs }.normalized())
+    }
+
+    // bit by bit long division, None when dividing by zero
+    pub fn div_rem(&self, other: &Self) -> Option<(Self, Self)> {
+        if other.is_zero() {
+            return None;
+        }
+
+        let mut quotient = vec![0; self.limbs.len()];
+        let mut remainder = BigUint::zero();
+        for i in (0..self.bits()).rev() {
+            remainder.shift_in(self.bit(i));
+            if remainder >= *other {
+                remainder = remainder.checked_sub(other).unwrap();
+                quotient[(i / 32) as usize] |= 1 << (i % 32);
+            }
+        }
+
+        Some((BigUint { limbs: quotient }.normalized(), remainder))
+    }
+
+    pub fn rem_u64(&self, m: u64) -> u64 {
+        assert_ne!(m, 0, "division by zero!");
+
+        self.limbs
+            .iter()
+            .rev()
+            .fold(0_u128, |acc, &x| (acc << 32 | x as u128) % m as u128) as u64
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(value: u64) -> Self {
+        BigUint {
+            limbs: vec![value as u32, (value >> 32) as u32],
+        }
+        .normalized()
+    }
+}
+
+impl std::ops::Add for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: Self) -> Self::Output {
+        let len = self.limbs.len().max(other.limbs.len());
+        let mut limbs = Vec::with_capacity(len + 1);
+        let mut carry = 0;
+        for i in 0..len {
+            let a = self.limbs.get(i).copied().unwrap_or(0) as u64;
+            let b = other.limbs.get(i).copied().unwrap_or(0) as u64;
+            let v = a + b + carry;
+            limbs.push(v as u32);
+            carry = v >> 32;
+        }
+        limbs.push(carry as u32);
+
+        BigUint { limbs }.normalized()
+    }
+}
+
+impl std::ops::Mul for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: Self) -> Self::Output {
+        let mut limbs = vec![0_u32; self.limbs.len() + other.limbs.len()];
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0_u64;
+            for (j, &b) in other.limbs.iter().enumerate() {
+                let v = limbs[i + j] as u64 + a as u64 * b as u64 + carry;
+                limbs[i + j] = v as u32;
+                carry = v >> 32;
+            }
+            limbs[i + other.limbs.len()] = carry as u32;
+        }
+
+        BigUint { limbs }.normalized()
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for BigUint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let chunk = BigUint::from(1_000_000_000);
+
+        let mut chunks = vec![];
+        let mut rest = self.clone();
+        while !rest.is_zero() {
+            let (q, r) = rest.div_rem(&chunk).unwrap();
+            chunks.push(r.to_u64().unwrap());
+            rest = q;
+        }
+
+        match chunks.split_last() {
+            None => write!(f, "0"),
+            Some((first, others)) => {
+                write!(f, "{first}")?;
+                others.iter().rev().try_for_each(|x| write!(f, "{x:09}"))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd(12, -18), 6_u32);
+        assert_eq!(gcd(0_u64, 7), 7);
+        assert_eq!(gcd_all([12, 18, 30]), 6_u32);
+        assert_eq!(lcm_all([23_u64, 19, 13, 17]), 96577);
+        assert_eq!(lcm_all([4, 6, 10]), 60);
+        assert_eq!(checked_lcm(u64::MAX, u64::MAX - 1), None);
+
+        assert_eq!(gcd(i64::MIN, 6), 2);
+        assert_eq!(gcd(i64::MIN, 0), 1 << 63);
+        assert_eq!(gcd(i128::MIN, i128::MIN), 1 << 127);
+        assert_eq!(gcd_all([i32::MIN, -12]), 4);
+        assert_eq!(checked_lcm(i64::MIN, 3), None);
+        assert_eq!(checked_lcm(i64::MIN / 2, -2), Some(1 << 62));
+        assert_eq!(
+            checked_lcm_all([1_u64 << 40, 3, 1 << 50, 5]),
+            Some(15 << 50)
+        );
+        assert_eq!(checked_lcm_all([u64::MAX, u64::MAX - 1]), None);
+    }
+
+    #[test]
+    fn test_modular() {
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(3, 0, 1), 0);
+        assert_eq!(mod_mul(u64::MAX - 1, u64::MAX - 1, u64::MAX), 1);
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(2, 4), None);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(1, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt(&[(0, 4), (1, 6)]), None);
+        assert_eq!(crt(&[(-1, 5)]), Some((4, 5)));
+        assert_eq!(crt(&[(2, 3), (0, 0)]), None);
+        assert_eq!(crt(&[(0, 0)]), None);
+    }
+
+    #[test]
+    fn test_rational() {
+        let a = Rational::new(1, 3);
+        let b = Rational::new(-1, 6);
+
+        assert_eq!(a + b, Rational::new(1, 6));
+        assert_eq!(a - b, Rational::new(1, 2));
+        assert_eq!(a * b, Rational::new(-1, 18));
+        assert_eq!(a / b, Rational::integer(-2));
+        assert!(b < a);
+        assert_eq!(Rational::new(4, -2).to_string(), "-2");
+        assert_eq!(a.checked_div(Rational::integer(0)), None);
+        assert_eq!(Rational::integer(i128::MAX).checked_add(a), None);
+    }
+
+    #[test]
+    fn test_rational_limits() {
+        let m = i128::MAX;
+
+        // 1 + 1/(m - 1) < 1 + 1/(m - 2), cross products overflow
+        let a = Rational::new(m, m - 1);
+        let b = Rational::new(m - 1, m - 2);
+        assert!(a < b);
+        assert!(Rational::new(1 - m, m - 2) < Rational::new(-m, m - 1));
+        assert_eq!(a.cmp(&a), Ordering::Equal);
+        assert!(Rational::new(m, 2) > Rational::new(m - 2, 2));
+        assert!(Rational::new(1, m) > Rational::new(-1, m));
+        assert!(Rational::new(2, m) > Rational::new(1, m - 1));
+        assert!(Rational::new(i128::MIN, m) < Rational::integer(-1));
+
+        assert_eq!(
+            Rational::new(i128::MIN, 2),
+            Rational::integer(i128::MIN / 2)
+        );
+        assert_eq!(Rational::new(i128::MIN, i128::MIN), Rational::integer(1));
+        assert_eq!(Rational::new(0, i128::MIN), Rational::integer(0));
+        assert_eq!(Rational::checked_new(i128::MIN, -1), None);
+        assert_eq!(Rational::checked_new(1, i128::MIN), None);
+        assert_eq!(Rational::checked_new(1, 0), None);
+        assert_eq!(
+            Rational::integer(i128::MIN).checked_div(Rational::integer(-1)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_big_uint() {
+        let a = BigUint::from(u64::MAX);
+        let b = &a * &a;
+
+        assert_eq!(b.to_string(), "340282366920938463426481119284349108225");
+        assert_eq!(b.bits(), 128);
+        assert_eq!(b.to_u64(), None);
+        assert_eq!(
+            b.rem_u64(1_000_000_007),
+            (u64::MAX as u128).pow(2).rem_euclid(1_000_000_007) as u64
+        );
+
+        let (q, r) = (&b + &BigUint::from(5)).div_rem(&a).unwrap();
+        assert_eq!((q, r), (a.clone(), BigUint::from(5)));
+
+        assert_eq!(a.checked_sub(&b), None);
+        assert_eq!(b.checked_sub(&b), Some(BigUint::zero()));
+        assert_eq!(a.div_rem(&BigUint::zero()), None);
+        assert_eq!(BigUint::zero().to_string(), "0");
+        assert_eq!(BigUint::from(1_000_000_000).to_string(), "1000000000");
+        assert!(BigUint::from(7) < BigUint::from(1 << 40));
+    }
+}
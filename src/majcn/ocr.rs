@@ -0,0 +1,209 @@
+use crate::majcn::list::Array2D;
+
+// glyphs are trimmed to their lit columns, letters are separated by at least one blank column
+const FONT_6: [(char, &str); 18] = [
+    ('A', ".##.\n#..#\n#..#\n####\n#..#\n#..#"),
+    ('B', "###.\n#..#\n###.\n#..#\n#..#\n###."),
+    ('C', ".##.\n#..#\n#...\n#...\n#..#\n.##."),
+    ('E', "####\n#...\n###.\n#...\n#...\n####"),
+    ('F', "####\n#...\n###.\n#...\n#...\n#..."),
+    ('G', ".##.\n#..#\n#...\n#.##\n#..#\n.###"),
+    ('H', "#..#\n#..#\n####\n#..#\n#..#\n#..#"),
+    ('I', "###\n.#.\n.#.\n.#.\n.#.\n###"),
+    ('J', "..##\n...#\n...#\n...#\n#..#\n.##."),
+    ('K', "#..#\n#.#.\n##..\n#.#.\n#.#.\n#..#"),
+    ('L', "#...\n#...\n#...\n#...\n#...\n####"),
+    ('O', ".##.\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('P', "###.\n#..#\n#..#\n###.\n#...\n#..."),
+    ('R', "###.\n#..#\n#..#\n###.\n#.#.\n#..#"),
+    ('S', ".###\n#...\n#...\n.##.\n...#\n###."),
+    ('U', "#..#\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('Y', "#...#\n#...#\n.#.#.\n..#..\n..#..\n..#.."),
+    ('Z', "####\n...#\n..#.\n.#..\n#...\n####"),
+];
+
+const FONT_10: [(char, &str); 15] = [
+    (
+        'A',
+        "..##..\n.#..#.\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'B',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#....#\n#....#\n#....#\n#....#\n#####.",
+    ),
+    (
+        'C',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#....#\n.####.",
+    ),
+    (
+        'E',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'F',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'G',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#..###\n#....#\n#....#\n#...##\n.###.#",
+    ),
+    (
+        'H',
+        "#....#\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'J',
+        "...###\n....#.\n....#.\n....#.\n....#.\n....#.\n#...#.\n#...#.\n#...#.\n.###..",
+    ),
+    (
+        'K',
+        "#....#\n#...#.\n#..#..\n#.#...\n##....\n##....\n#.#...\n#..#..\n#...#.\n#....#",
+    ),
+    (
+        'L',
+        "#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'N',
+        "#....#\n##...#\n##...#\n#.#..#\n#.#..#\n#..#.#\n#..#.#\n#...##\n#...##\n#....#",
+    ),
+    (
+        'P',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'R',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#..#..\n#...#.\n#...#.\n#....#\n#....#",
+    ),
+    (
+        'X',
+        "#....#\n#....#\n.#..#.\n.#..#.\n..##..\n..##..\n.#..#.\n.#..#.\n#....#\n#....#",
+    ),
+    (
+        'Z',
+        "######\n.....#\n.....#\n....#.\n...#..\n..#...\n.#....\n#.....\n#.....\n######",
+    ),
+];
+
+pub fn recognize(bitmap: &Array2D<bool>) -> Result<String, String> {
+    let is_row_blank = |y: usize| (0..bitmap.len_line()).all(|x| !bitmap[(x, y)]);
+
+    let rows = (0..bitmap.len())
+        .filter(|&y| !is_row_blank(y))
+        .collect::<Vec<_>>();
+    let (Some(&top), Some(&bottom)) = (rows.first(), rows.last()) else {
+        return Ok(String::new());
+    };
+
+    let font = match bottom - top + 1 {
+        6 => &FONT_6[..],
+        10 => &FONT_10[..],
+        height => return Err(format!("no font with letter height {height}")),
+    };
+
+    let is_column_blank = |x: usize| (top..=bottom).all(|y| !bitmap[(x, y)]);
+
+    let mut result = String::new();
+    let mut x = 0;
+    while x < bitmap.len_line() {
+        if is_column_blank(x) {
+            x += 1;
+            continue;
+        }
+
+        let start = x;
+        while x < bitmap.len_line() && !is_column_blank(x) {
+            x += 1;
+        }
+
+        let glyph = (top..=bottom)
+            .map(|y| {
+                (start..x)
+                    .map(|x| if bitmap[(x, y)] { '#' } else { '.' })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let letter = font
+            .iter()
+            .find(|(_, pattern)| *pattern == glyph)
+            .map(|(c, _)| *c)
+            .ok_or_else(|| format!("unknown glyph at column {start}:\n{glyph}"))?;
+
+        result.push(letter);
+    }
+
+    Ok(result)
+}
+
+// '#' is lit, anything else is dark, lines may have different lengths
+pub fn recognize_str(rendering: &str) -> Result<String, String> {
+    let lines = rendering.lines().collect::<Vec<_>>();
+    let width = lines.iter().map(|x| x.chars().count()).max().unwrap_or(0);
+    if width == 0 {
+        return Ok(String::new());
+    }
+
+    let mut bitmap = Array2D::new(width);
+    for line in lines {
+        let lit = line.chars().map(|c| c == '#');
+        bitmap.add_line(lit.chain(std::iter::repeat(false)).take(width));
+    }
+
+    recognize(&bitmap)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(text: &str, font: &[(char, &str)]) -> String {
+        let glyphs = text
+            .chars()
+            .map(|c| font.iter().find(|x| x.0 == c).unwrap().1)
+            .map(|x| x.lines().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        (0..glyphs[0].len())
+            .map(|y| {
+                glyphs
+                    .iter()
+                    .map(|g| g[y])
+                    .collect::<Vec<_>>()
+                    .join(" ")
+                    .replace('.', " ")
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_small_font() {
+        let text = FONT_6.iter().map(|x| x.0).collect::<String>();
+        assert_eq!(recognize_str(&render(&text, &FONT_6)), Ok(text));
+
+        let rendering = "\
+###  #### #    #  # ###  ###  #### #  #
+#  # #    #    #  # #  # #  #    # #  #
+#  # ###  #    #  # #  # #  #   #  ####
+###  #    #    #  # ###  ###   #   #  #
+#    #    #    #  # #    # #  #    #  #
+#    #### ####  ##  #    #  # #### #  #
+";
+        assert_eq!(recognize_str(rendering), Ok(String::from("PELUPRZH")));
+    }
+
+    #[test]
+    fn test_large_font() {
+        let text = FONT_10.iter().map(|x| x.0).collect::<String>();
+        assert_eq!(recognize_str(&render(&text, &FONT_10)), Ok(text));
+    }
+
+    #[test]
+    fn test_unknown_glyph() {
+        assert!(recognize_str("#\n#\n#\n#\n#\n#").is_err());
+        assert!(recognize_str("##\n##").is_err());
+        assert_eq!(recognize_str("\n\n"), Ok(String::new()));
+    }
+}
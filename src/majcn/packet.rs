@@ -0,0 +1,314 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+use tinyjson::JsonValue;
+
+// nested list of non-negative integers, like [1,[2,[3,[4,[5,6,7]]]],8,9]
+// equality follows the ordering, so 2, [2] and [[2]] are the same packet
+#[derive(Debug, Clone)]
+pub enum Packet {
+    Int(u32),
+    List(Vec<Packet>),
+}
+
+impl Packet {
+    pub fn list(values: impl IntoIterator<Item = Packet>) -> Self {
+        Packet::List(values.into_iter().collect())
+    }
+
+    // [[n]], the divider packets of day 13
+    pub fn divider(n: u32) -> Self {
+        Packet::list([Packet::list([Packet::Int(n)])])
+    }
+
+    // integer wrapped in any number of single element lists
+    fn as_int(&self) -> Option<u32> {
+        match self {
+            Packet::Int(v) => Some(*v),
+            Packet::List(values) => match &values[..] {
+                [v] => v.as_int(),
+                _ => None,
+            },
+        }
+    }
+}
+
+impl PartialEq for Packet {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Packet {}
+
+// packets which compare equal either wrap the same integer or are lists of
+// equal packets
+impl Hash for Packet {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match (self.as_int(), self) {
+            (Some(v), _) => {
+                0_u8.hash(state);
+                v.hash(state);
+            }
+            (None, Packet::List(values)) => {
+                1_u8.hash(state);
+                values.hash(state);
+            }
+            (None, Packet::Int(_)) => unreachable!(),
+        }
+    }
+}
+
+impl Ord for Packet {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // an integer compared with a list acts as a list with one element
+        match (self, other) {
+            (Packet::Int(a), Packet::Int(b)) => a.cmp(b),
+            (Packet::List(a), Packet::List(b)) => a.iter().cmp(b.iter()),
+            (Packet::Int(_), Packet::List(b)) => std::slice::from_ref(self).iter().cmp(b.iter()),
+            (Packet::List(a), Packet::Int(_)) => a.iter().cmp(std::slice::from_ref(other).iter()),
+        }
+    }
+}
+
+impl PartialOrd for Packet {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for Packet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Packet::Int(v) => write!(f, "{v}"),
+            Packet::List(values) => {
+                write!(f, "[")?;
+                for (i, v) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{v}")?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl Parser<'_> {
+    fn error(&mut self, expected: &str) -> String {
+        match self.chars.peek() {
+            Some(&(i, c)) => format!("expected {expected} at {i}, found '{c}'"),
+            None => format!("expected {expected} at {}, found end", self.input.len()),
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Packet, String> {
+        match self.chars.peek() {
+            Some((_, '[')) => self.parse_list(),
+            Some((_, c)) if c.is_ascii_digit() => self.parse_int(),
+            _ => Err(self.error("'[' or digit")),
+        }
+    }
+
+    fn parse_int(&mut self) -> Result<Packet, String> {
+        let mut result: u32 = 0;
+        while let Some(&(i, c)) = self.chars.peek() {
+            let Some(digit) = c.to_digit(10) else {
+                break;
+            };
+
+            result = result
+                .checked_mul(10)
+                .and_then(|x| x.checked_add(digit))
+                .ok_or_else(|| format!("number too big at {i}"))?;
+            self.chars.next();
+        }
+
+        Ok(Packet::Int(result))
+    }
+
+    fn parse_list(&mut self) -> Result<Packet, String> {
+        self.chars.next();
+
+        let mut result = vec![];
+        if let Some((_, ']')) = self.chars.peek() {
+            self.chars.next();
+            return Ok(Packet::List(result));
+        }
+
+        loop {
+            result.push(self.parse_value()?);
+
+            match self.chars.peek() {
+                Some((_, ',')) => {
+                    self.chars.next();
+                }
+                Some((_, ']')) => {
+                    self.chars.next();
+                    return Ok(Packet::List(result));
+                }
+                _ => return Err(self.error("',' or ']'")),
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for Packet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            input: s,
+            chars: s.char_indices().peekable(),
+        };
+
+        let result = parser.parse_value()?;
+        if parser.chars.peek().is_some() {
+            return Err(parser.error("end"));
+        }
+
+        Ok(result)
+    }
+}
+
+impl From<&Packet> for JsonValue {
+    fn from(packet: &Packet) -> Self {
+        match packet {
+            Packet::Int(v) => JsonValue::Number(*v as f64),
+            Packet::List(values) => JsonValue::Array(values.iter().map(JsonValue::from).collect()),
+        }
+    }
+}
+
+impl TryFrom<&JsonValue> for Packet {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        match value {
+            JsonValue::Number(v) if v.fract() == 0.0 && (0.0..=u32::MAX as f64).contains(v) => {
+                Ok(Packet::Int(*v as u32))
+            }
+            JsonValue::Array(values) => values
+                .iter()
+                .map(Packet::try_from)
+                .collect::<Result<_, _>>()
+                .map(Packet::List),
+            _ => Err(format!("not a packet value: {value:?}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn p(s: &str) -> Packet {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        for s in [
+            "[]",
+            "[[]]",
+            "7",
+            "[1,[2,[3,[4,[5,6,7]]]],8,9]",
+            "[10,[],0]",
+        ] {
+            assert_eq!(p(s).to_string(), s);
+        }
+
+        assert_eq!(
+            "[1,2".parse::<Packet>(),
+            Err(String::from("expected ',' or ']' at 4, found end"))
+        );
+        assert_eq!(
+            "[1,]".parse::<Packet>(),
+            Err(String::from("expected '[' or digit at 3, found ']'"))
+        );
+        assert!("[1]]".parse::<Packet>().is_err());
+        assert!("[99999999999]".parse::<Packet>().is_err());
+        assert!("".parse::<Packet>().is_err());
+    }
+
+    #[test]
+    fn test_ordering() {
+        assert!(p("[1,1,3,1,1]") < p("[1,1,5,1,1]"));
+        assert!(p("[[1],[2,3,4]]") < p("[[1],4]"));
+        assert!(p("[9]") > p("[[8,7,6]]"));
+        assert!(p("[[4,4],4,4]") < p("[[4,4],4,4,4]"));
+        assert!(p("[7,7,7,7]") > p("[7,7,7]"));
+        assert!(p("[]") < p("[3]"));
+        assert!(p("[[[]]]") > p("[[]]"));
+        assert!(p("[1,[2,[3,[4,[5,6,7]]]],8,9]") > p("[1,[2,[3,[4,[5,6,0]]]],8,9]"));
+        assert_eq!(p("[[2]]").cmp(&p("2")), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_eq_follows_ordering() {
+        let packets = [
+            "2",
+            "[2]",
+            "[[2]]",
+            "[[[2]]]",
+            "3",
+            "[]",
+            "[[]]",
+            "[[[]]]",
+            "[2,3]",
+            "[[2],3]",
+            "[2,[3]]",
+            "[[2,3]]",
+            "[[2,3],[]]",
+            "[[2,3],[[]]]",
+        ]
+        .map(p);
+
+        let hash = |x: &Packet| {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            x.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        for a in packets.iter() {
+            for b in packets.iter() {
+                assert_eq!(a == b, a.cmp(b) == Ordering::Equal, "{a} and {b}");
+                if a == b {
+                    assert_eq!(hash(a), hash(b), "{a} and {b}");
+                }
+            }
+        }
+
+        assert_eq!(p("[[2]]"), p("2"));
+        assert_ne!(p("[[2,3]]"), p("[2,3]"));
+        assert_ne!(p("[[]]"), p("[]"));
+
+        let unique = packets.iter().collect::<std::collections::HashSet<_>>();
+        assert_eq!(unique.len(), 9);
+        let mut sorted = packets.to_vec();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(sorted.len(), 9);
+    }
+
+    #[test]
+    fn test_json() {
+        let packet = p("[1,[2,[]],3]");
+        let json = JsonValue::from(&packet);
+
+        assert_eq!(json.stringify().unwrap(), "[1,[2,[]],3]");
+        assert_eq!(Packet::try_from(&json), Ok(packet));
+
+        let json = "[1, 2.5]".parse::<JsonValue>().unwrap();
+        assert!(Packet::try_from(&json).is_err());
+    }
+}
@@ -1,8 +1,24 @@
+// UP and DOWN follow the mathematical convention where y grows upwards. Grids
+// which are parsed line by line use the screen convention instead (y is the row
+// index and grows downwards), see Direction::offset and Direction::screen_offset.
 pub const LEFT: Point = Point { x: -1, y: 0 };
 pub const RIGHT: Point = Point { x: 1, y: 0 };
 pub const UP: Point = Point { x: 0, y: 1 };
 pub const DOWN: Point = Point { x: 0, y: -1 };
 
+pub const NEIGHBORS_4: [Point; 4] = [UP, RIGHT, DOWN, LEFT];
+
+pub const NEIGHBORS_8: [Point; 8] = [
+    Point { x: -1, y: 0 },
+    Point { x: -1, y: 1 },
+    Point { x: 0, y: 1 },
+    Point { x: 1, y: 1 },
+    Point { x: 1, y: 0 },
+    Point { x: 1, y: -1 },
+    Point { x: 0, y: -1 },
+    Point { x: -1, y: -1 },
+];
+
 #[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, PartialOrd, Ord)]
 pub struct Point {
     pub x: i32,
@@ -19,6 +35,151 @@ impl Point {
     pub fn manhattan(self, other: Point) -> i32 {
         (self.x - other.x).abs() + (self.y - other.y).abs()
     }
+
+    #[inline]
+    pub fn chebyshev(self, other: Point) -> i32 {
+        i32::max((self.x - other.x).abs(), (self.y - other.y).abs())
+    }
+
+    #[inline]
+    pub fn signum(self) -> Point {
+        Point {
+            x: self.x.signum(),
+            y: self.y.signum(),
+        }
+    }
+
+    pub fn neighbors_4(self) -> impl Iterator<Item = Point> {
+        NEIGHBORS_4.into_iter().map(move |n| self + n)
+    }
+
+    pub fn neighbors_8(self) -> impl Iterator<Item = Point> {
+        NEIGHBORS_8.into_iter().map(move |n| self + n)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum Rotation {
+    Clockwise,
+    CounterClockwise,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    pub const fn turn_right(self) -> Self {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    pub const fn turn_left(self) -> Self {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Left => Direction::Down,
+            Direction::Down => Direction::Right,
+            Direction::Right => Direction::Up,
+        }
+    }
+
+    pub const fn opposite(self) -> Self {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Right => Direction::Left,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+        }
+    }
+
+    pub const fn rotate(self, rotation: Rotation) -> Self {
+        match rotation {
+            Rotation::Clockwise => self.turn_right(),
+            Rotation::CounterClockwise => self.turn_left(),
+        }
+    }
+
+    // y grows upwards, same as UP and DOWN constants
+    pub const fn offset(self) -> Point {
+        match self {
+            Direction::Up => UP,
+            Direction::Right => RIGHT,
+            Direction::Down => DOWN,
+            Direction::Left => LEFT,
+        }
+    }
+
+    // y grows downwards, for grids where y is the line index
+    pub const fn screen_offset(self) -> Point {
+        match self {
+            Direction::Up => DOWN,
+            Direction::Right => RIGHT,
+            Direction::Down => UP,
+            Direction::Left => LEFT,
+        }
+    }
+}
+
+impl TryFrom<u8> for Direction {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            b'U' | b'^' | b'N' => Ok(Direction::Up),
+            b'R' | b'>' | b'E' => Ok(Direction::Right),
+            b'D' | b'v' | b'S' => Ok(Direction::Down),
+            b'L' | b'<' | b'W' => Ok(Direction::Left),
+            _ => Err(format!("invalid direction: {}", value as char)),
+        }
+    }
+}
+
+impl TryFrom<char> for Direction {
+    type Error = String;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        u8::try_from(value)
+            .map_err(|_| format!("invalid direction: {value}"))
+            .and_then(Direction::try_from)
+    }
+}
+
+impl std::str::FromStr for Direction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.as_bytes() {
+            [v] => Direction::try_from(*v),
+            _ => Err(format!("invalid direction: {s}")),
+        }
+    }
+}
+
+impl TryFrom<u8> for Rotation {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            b'R' => Ok(Rotation::Clockwise),
+            b'L' => Ok(Rotation::CounterClockwise),
+            _ => Err(format!("invalid rotation: {}", value as char)),
+        }
+    }
 }
 
 impl std::ops::Add for Point {
@@ -60,3 +221,108 @@ impl std::ops::SubAssign for Point {
         self.y -= rhs.y;
     }
 }
+
+impl std::ops::Neg for Point {
+    type Output = Point;
+
+    #[inline]
+    fn neg(self) -> Self::Output {
+        Point {
+            x: -self.x,
+            y: -self.y,
+        }
+    }
+}
+
+impl std::ops::Mul<i32> for Point {
+    type Output = Point;
+
+    #[inline]
+    fn mul(self, rhs: i32) -> Self::Output {
+        Point {
+            x: self.x * rhs,
+            y: self.y * rhs,
+        }
+    }
+}
+
+impl std::ops::MulAssign<i32> for Point {
+    #[inline]
+    fn mul_assign(&mut self, rhs: i32) {
+        self.x *= rhs;
+        self.y *= rhs;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        for (inputs, expected) in [
+            ("U^N", Direction::Up),
+            ("R>E", Direction::Right),
+            ("DvS", Direction::Down),
+            ("L<W", Direction::Left),
+        ] {
+            for c in inputs.chars() {
+                assert_eq!(Direction::try_from(c), Ok(expected));
+                assert_eq!(Direction::try_from(c as u8), Ok(expected));
+                assert_eq!(c.to_string().parse::<Direction>(), Ok(expected));
+            }
+        }
+
+        assert!(Direction::try_from('x').is_err());
+        assert!(Direction::try_from('č').is_err());
+        assert!("".parse::<Direction>().is_err());
+        assert!("UD".parse::<Direction>().is_err());
+
+        assert_eq!(Rotation::try_from(b'R'), Ok(Rotation::Clockwise));
+        assert_eq!(Rotation::try_from(b'L'), Ok(Rotation::CounterClockwise));
+        assert!(Rotation::try_from(b'U').is_err());
+    }
+
+    #[test]
+    fn test_offsets() {
+        assert_eq!(Direction::Up.offset(), Point::new(0, 1));
+        assert_eq!(Direction::Down.offset(), Point::new(0, -1));
+        assert_eq!(Direction::Up.screen_offset(), Point::new(0, -1));
+        assert_eq!(Direction::Down.screen_offset(), Point::new(0, 1));
+
+        for d in Direction::ALL {
+            let (offset, screen) = (d.offset(), d.screen_offset());
+            // horizontal moves agree, vertical ones are mirrored
+            assert_eq!(offset.x, screen.x);
+            assert_eq!(offset.y, -screen.y);
+            assert_eq!(offset.manhattan(Point::new(0, 0)), 1);
+            assert_eq!(d.opposite().offset(), -offset);
+            assert_eq!(d.opposite().screen_offset(), -screen);
+        }
+    }
+
+    #[test]
+    fn test_turns() {
+        // ALL lists the directions clockwise
+        for (i, d) in Direction::ALL.into_iter().enumerate() {
+            assert_eq!(d.turn_right(), Direction::ALL[(i + 1) % 4]);
+            assert_eq!(d.turn_left(), Direction::ALL[(i + 3) % 4]);
+
+            assert_eq!(d.turn_right().turn_left(), d);
+            assert_eq!(d.turn_left().turn_right(), d);
+            assert_eq!(d.turn_right().turn_right(), d.opposite());
+            assert_eq!(d.opposite().opposite(), d);
+
+            let mut x = d;
+            for _ in 0..4 {
+                x = x.rotate(Rotation::Clockwise);
+            }
+            assert_eq!(x, d);
+            assert_eq!(
+                d.rotate(Rotation::Clockwise)
+                    .rotate(Rotation::CounterClockwise),
+                d
+            );
+        }
+    }
+}
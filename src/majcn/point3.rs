@@ -0,0 +1,418 @@
+pub const NEIGHBORS_6: [Point3; 6] = [
+    Point3::new(1, 0, 0),
+    Point3::new(-1, 0, 0),
+    Point3::new(0, 1, 0),
+    Point3::new(0, -1, 0),
+    Point3::new(0, 0, 1),
+    Point3::new(0, 0, -1),
+];
+
+pub const NEIGHBORS_26: [Point3; 26] = neighbors_26();
+
+const fn neighbors_26() -> [Point3; 26] {
+    let mut result = [Point3::new(0, 0, 0); 26];
+    let mut i = 0;
+    let mut j = 0;
+    while i < 27 {
+        if i != 13 {
+            result[j] = Point3::new(i % 3 - 1, i / 3 % 3 - 1, i / 9 - 1);
+            j += 1;
+        }
+        i += 1;
+    }
+    result
+}
+
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, PartialOrd, Ord, Default)]
+pub struct Point3 {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+impl Point3 {
+    pub const fn new(x: i32, y: i32, z: i32) -> Self {
+        Point3 { x, y, z }
+    }
+
+    #[inline]
+    pub fn manhattan(self, other: Point3) -> i32 {
+        (self.x - other.x).abs() + (self.y - other.y).abs() + (self.z - other.z).abs()
+    }
+
+    #[inline]
+    pub fn min(self, other: Point3) -> Point3 {
+        Point3::new(
+            i32::min(self.x, other.x),
+            i32::min(self.y, other.y),
+            i32::min(self.z, other.z),
+        )
+    }
+
+    #[inline]
+    pub fn max(self, other: Point3) -> Point3 {
+        Point3::new(
+            i32::max(self.x, other.x),
+            i32::max(self.y, other.y),
+            i32::max(self.z, other.z),
+        )
+    }
+
+    pub fn neighbors_6(self) -> impl Iterator<Item = Point3> {
+        NEIGHBORS_6.into_iter().map(move |n| self + n)
+    }
+
+    pub fn neighbors_26(self) -> impl Iterator<Item = Point3> {
+        NEIGHBORS_26.into_iter().map(move |n| self + n)
+    }
+}
+
+impl std::str::FromStr for Point3 {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut iter = s.trim().splitn(3, ',').map(|v| v.trim().parse::<i32>());
+        match (iter.next(), iter.next(), iter.next()) {
+            (Some(Ok(x)), Some(Ok(y)), Some(Ok(z))) => Ok(Point3::new(x, y, z)),
+            _ => Err(format!("invalid point3: {s}")),
+        }
+    }
+}
+
+impl std::ops::Add for Point3 {
+    type Output = Point3;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self::Output {
+        Point3 {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+            z: self.z + rhs.z,
+        }
+    }
+}
+
+impl std::ops::AddAssign for Point3 {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+        self.z += rhs.z;
+    }
+}
+
+impl std::ops::Sub for Point3 {
+    type Output = Point3;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self::Output {
+        Point3 {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+            z: self.z - rhs.z,
+        }
+    }
+}
+
+impl std::ops::SubAssign for Point3 {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Self) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+        self.z -= rhs.z;
+    }
+}
+
+impl std::ops::Neg for Point3 {
+    type Output = Point3;
+
+    #[inline]
+    fn neg(self) -> Self::Output {
+        Point3 {
+            x: -self.x,
+            y: -self.y,
+            z: -self.z,
+        }
+    }
+}
+
+impl std::ops::Mul<i32> for Point3 {
+    type Output = Point3;
+
+    #[inline]
+    fn mul(self, rhs: i32) -> Self::Output {
+        Point3 {
+            x: self.x * rhs,
+            y: self.y * rhs,
+            z: self.z * rhs,
+        }
+    }
+}
+
+impl std::ops::MulAssign<i32> for Point3 {
+    #[inline]
+    fn mul_assign(&mut self, rhs: i32) {
+        self.x *= rhs;
+        self.y *= rhs;
+        self.z *= rhs;
+    }
+}
+
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub struct BBox3 {
+    pub min: Point3,
+    pub max: Point3,
+}
+
+impl BBox3 {
+    pub fn new(min: Point3, max: Point3) -> Self {
+        BBox3 { min, max }
+    }
+
+    pub fn from_points(points: impl IntoIterator<Item = Point3>) -> Option<Self> {
+        let mut iter = points.into_iter();
+        let first = iter.next()?;
+
+        Some(iter.fold(BBox3::new(first, first), |bbox, p| {
+            BBox3::new(bbox.min.min(p), bbox.max.max(p))
+        }))
+    }
+
+    pub fn expand(&self, n: i32) -> Self {
+        let offset = Point3::new(n, n, n);
+        BBox3::new(self.min - offset, self.max + offset)
+    }
+
+    #[inline]
+    pub fn contains(&self, p: &Point3) -> bool {
+        (self.min.x..=self.max.x).contains(&p.x)
+            && (self.min.y..=self.max.y).contains(&p.y)
+            && (self.min.z..=self.max.z).contains(&p.z)
+    }
+
+    #[inline]
+    pub fn size(&self) -> Point3 {
+        self.max - self.min + Point3::new(1, 1, 1)
+    }
+
+    pub fn volume(&self) -> usize {
+        let size = self.size();
+        size.x as usize * size.y as usize * size.z as usize
+    }
+
+    pub fn is_on_boundary(&self, p: &Point3) -> bool {
+        self.contains(p)
+            && (p.x == self.min.x
+                || p.x == self.max.x
+                || p.y == self.min.y
+                || p.y == self.max.y
+                || p.z == self.min.z
+                || p.z == self.max.z)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Point3> {
+        let BBox3 { min, max } = *self;
+        (min.z..=max.z).flat_map(move |z| {
+            (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| Point3::new(x, y, z)))
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct VoxelGrid<T> {
+    bbox: BBox3,
+    raw_data: Vec<T>,
+}
+
+impl<T: Clone> VoxelGrid<T> {
+    pub fn new(bbox: BBox3, value: T) -> Self {
+        VoxelGrid {
+            bbox,
+            raw_data: vec![value; bbox.volume()],
+        }
+    }
+}
+
+impl<T> VoxelGrid<T> {
+    pub fn bbox(&self) -> BBox3 {
+        self.bbox
+    }
+
+    #[inline]
+    fn offset(&self, p: &Point3) -> Option<usize> {
+        if !self.bbox.contains(p) {
+            return None;
+        }
+
+        let size = self.bbox.size();
+        let local = *p - self.bbox.min;
+        Some(((local.z * size.y + local.y) * size.x + local.x) as usize)
+    }
+
+    pub fn get(&self, p: &Point3) -> Option<&T> {
+        self.offset(p).map(|i| &self.raw_data[i])
+    }
+
+    pub fn get_mut(&mut self, p: &Point3) -> Option<&mut T> {
+        self.offset(p).map(|i| &mut self.raw_data[i])
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point3, &T)> {
+        self.bbox.iter().zip(self.raw_data.iter())
+    }
+
+    pub fn flood_fill<F>(
+        &self,
+        starts: impl IntoIterator<Item = Point3>,
+        passable: F,
+    ) -> VoxelGrid<bool>
+    where
+        F: Fn(&T) -> bool,
+    {
+        let mut visited = VoxelGrid::new(self.bbox, false);
+
+        let mut queue = vec![];
+        for start in starts {
+            if self.get(&start).is_some_and(&passable) && !visited[start] {
+                visited[start] = true;
+                queue.push(start);
+            }
+        }
+
+        while let Some(p) = queue.pop() {
+            for neighbor in p.neighbors_6() {
+                if self.get(&neighbor).is_some_and(&passable) && !visited[neighbor] {
+                    visited[neighbor] = true;
+                    queue.push(neighbor);
+                }
+            }
+        }
+
+        visited
+    }
+}
+
+impl VoxelGrid<bool> {
+    // bounding box is padded by one so the exterior always wraps around the shape
+    pub fn from_points(points: impl IntoIterator<Item = Point3>) -> Option<Self> {
+        let points = points.into_iter().collect::<Vec<_>>();
+        let bbox = BBox3::from_points(points.iter().copied())?.expand(1);
+
+        let mut grid = VoxelGrid::new(bbox, false);
+        for p in points {
+            grid[p] = true;
+        }
+
+        Some(grid)
+    }
+
+    pub fn surface_area(&self) -> usize {
+        self.iter()
+            .filter(|(_, &filled)| filled)
+            .flat_map(|(p, _)| p.neighbors_6())
+            .filter(|n| !self.get(n).copied().unwrap_or(false))
+            .count()
+    }
+
+    // faces which can be reached from outside of the bounding box
+    pub fn exterior_surface_area(&self) -> usize {
+        let boundary = self.bbox.iter().filter(|p| self.bbox.is_on_boundary(p));
+        let exterior = self.flood_fill(boundary, |&filled| !filled);
+
+        self.iter()
+            .filter(|(_, &filled)| filled)
+            .flat_map(|(p, _)| p.neighbors_6())
+            .filter(|n| exterior.get(n).copied().unwrap_or(true))
+            .count()
+    }
+}
+
+impl<T> std::ops::Index<Point3> for VoxelGrid<T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: Point3) -> &Self::Output {
+        let i = self.offset(&index).expect("point outside of voxel grid");
+        &self.raw_data[i]
+    }
+}
+
+impl<T> std::ops::IndexMut<Point3> for VoxelGrid<T> {
+    #[inline]
+    fn index_mut(&mut self, index: Point3) -> &mut Self::Output {
+        let i = self.offset(&index).expect("point outside of voxel grid");
+        &mut self.raw_data[i]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(input: &str) -> VoxelGrid<bool> {
+        VoxelGrid::from_points(input.split_whitespace().map(|x| x.parse().unwrap())).unwrap()
+    }
+
+    #[test]
+    fn test_example() {
+        let example = "2,2,2 1,2,2 3,2,2 2,1,2 2,3,2 2,2,1 2,2,3 2,2,4 2,2,6 \
+                       1,2,5 3,2,5 2,1,5 2,3,5";
+        let example = grid(example);
+
+        assert_eq!(example.surface_area(), 64);
+        assert_eq!(example.exterior_surface_area(), 58);
+    }
+
+    #[test]
+    fn test_hollow_cube() {
+        // 3x3x3 cube with the center missing
+        let bbox = BBox3::new(Point3::new(0, 0, 0), Point3::new(2, 2, 2));
+        let hollow =
+            VoxelGrid::from_points(bbox.iter().filter(|&p| p != Point3::new(1, 1, 1))).unwrap();
+
+        assert_eq!(hollow.surface_area(), 6 * 9 + 6);
+        assert_eq!(hollow.exterior_surface_area(), 6 * 9);
+
+        let single = grid("5,-3,7");
+        assert_eq!(single.surface_area(), 6);
+        assert_eq!(single.exterior_surface_area(), 6);
+        assert!(VoxelGrid::from_points(std::iter::empty()).is_none());
+    }
+
+    #[test]
+    fn test_bbox() {
+        let points = [
+            Point3::new(1, -2, 3),
+            Point3::new(-1, 4, 0),
+            Point3::new(2, 0, 1),
+        ];
+        let bbox = BBox3::from_points(points).unwrap();
+
+        assert_eq!(bbox.min, Point3::new(-1, -2, 0));
+        assert_eq!(bbox.max, Point3::new(2, 4, 3));
+        assert_eq!(bbox.size(), Point3::new(4, 7, 4));
+        assert_eq!(bbox.volume(), 112);
+        assert_eq!(bbox.iter().count(), bbox.volume());
+        assert!(points.iter().all(|p| bbox.contains(p)));
+
+        assert!(bbox.contains(&Point3::new(-1, 4, 3)));
+        assert!(!bbox.contains(&Point3::new(-2, 0, 0)));
+        assert!(!bbox.contains(&Point3::new(0, 5, 0)));
+        assert!(!bbox.contains(&Point3::new(0, 0, 4)));
+
+        assert!(bbox.is_on_boundary(&Point3::new(2, 0, 1)));
+        assert!(!bbox.is_on_boundary(&Point3::new(0, 0, 1)));
+        assert!(!bbox.is_on_boundary(&Point3::new(3, 0, 1)));
+
+        let expanded = bbox.expand(1);
+        assert_eq!(expanded.size(), Point3::new(6, 9, 6));
+        assert!(expanded.contains(&Point3::new(-2, 5, -1)));
+        assert!(BBox3::from_points([]).is_none());
+
+        let mut voxels = VoxelGrid::new(bbox, 0);
+        voxels[Point3::new(2, 4, 3)] = 7;
+        assert_eq!(voxels.get(&Point3::new(2, 4, 3)), Some(&7));
+        assert_eq!(voxels.get(&Point3::new(3, 4, 3)), None);
+        assert_eq!(voxels.iter().filter(|(_, &v)| v == 7).count(), 1);
+    }
+}
@@ -0,0 +1,240 @@
+use std::collections::HashSet;
+
+use crate::majcn::point::Point;
+
+// knot 0 is the head, y grows upwards
+pub struct Rope {
+    start: Point,
+    knots: Vec<Point>,
+    visited: Vec<HashSet<Point>>,
+    trace: Option<Vec<Vec<Point>>>,
+}
+
+impl Rope {
+    pub fn new(knots: usize) -> Self {
+        assert!(knots > 0, "rope without knots!");
+
+        let start = Point::new(0, 0);
+        Rope {
+            start,
+            knots: vec![start; knots],
+            visited: vec![HashSet::from([start]); knots],
+            trace: None,
+        }
+    }
+
+    // remembers the knots after every step
+    pub fn with_trace(mut self) -> Self {
+        self.trace = Some(vec![self.knots.clone()]);
+        self
+    }
+
+    pub fn knots(&self) -> &[Point] {
+        &self.knots
+    }
+
+    pub fn head(&self) -> Point {
+        self.knots[0]
+    }
+
+    pub fn tail(&self) -> Point {
+        *self.knots.last().unwrap()
+    }
+
+    pub fn visited(&self, knot: usize) -> &HashSet<Point> {
+        &self.visited[knot]
+    }
+
+    pub fn tail_visited(&self) -> &HashSet<Point> {
+        self.visited.last().unwrap()
+    }
+
+    pub fn trace(&self) -> &[Vec<Point>] {
+        self.trace.as_deref().unwrap_or_default()
+    }
+
+    // moves the head by one cell, diagonal steps included
+    pub fn step(&mut self, offset: Point) {
+        assert!(
+            offset.chebyshev(Point::new(0, 0)) <= 1,
+            "head moves too far!"
+        );
+
+        self.knots[0] += offset;
+        self.visited[0].insert(self.knots[0]);
+
+        for i in 1..self.knots.len() {
+            let leader = self.knots[i - 1];
+            let knot = self.knots[i];
+            if leader.chebyshev(knot) <= 1 {
+                break;
+            }
+
+            self.knots[i] = knot + (leader - knot).signum();
+            self.visited[i].insert(self.knots[i]);
+        }
+
+        if let Some(trace) = self.trace.as_mut() {
+            trace.push(self.knots.clone());
+        }
+    }
+
+    pub fn move_by(&mut self, offset: Point, steps: u32) {
+        for _ in 0..steps {
+            self.step(offset);
+        }
+    }
+
+    fn label(&self, knot: usize) -> char {
+        match (knot, self.knots.len()) {
+            (0, _) => 'H',
+            (_, 2) => 'T',
+            (k, _) if k < 10 => char::from_digit(k as u32, 10).unwrap(),
+            _ => '#',
+        }
+    }
+
+    // like the puzzle pictures, top row first, earlier knots cover later ones
+    fn render_knots(&self, knots: &[Point], min: Point, max: Point) -> String {
+        let mut result = String::new();
+        for y in (min.y..=max.y).rev() {
+            for x in min.x..=max.x {
+                let p = Point::new(x, y);
+                let c = match knots.iter().position(|&k| k == p) {
+                    Some(i) => self.label(i),
+                    None if p == self.start => 's',
+                    None => '.',
+                };
+                result.push(c);
+            }
+            result.push('\n');
+        }
+        result
+    }
+
+    // both corners inclusive
+    pub fn render(&self, min: Point, max: Point) -> String {
+        self.render_knots(&self.knots, min, max)
+    }
+
+    // every traced state, all frames share the bounds of the whole trace
+    pub fn frames(&self) -> Vec<String> {
+        let all = self.trace().iter().flatten();
+        let min = all.clone().fold(self.start, |acc, p| {
+            Point::new(acc.x.min(p.x), acc.y.min(p.y))
+        });
+        let max = all.fold(self.start, |acc, p| {
+            Point::new(acc.x.max(p.x), acc.y.max(p.y))
+        });
+
+        self.trace()
+            .iter()
+            .map(|knots| self.render_knots(knots, min, max))
+            .collect()
+    }
+
+    pub fn render_visited(&self, knot: usize) -> String {
+        let visited = &self.visited[knot];
+        let min = visited.iter().fold(self.start, |acc, p| {
+            Point::new(acc.x.min(p.x), acc.y.min(p.y))
+        });
+        let max = visited.iter().fold(self.start, |acc, p| {
+            Point::new(acc.x.max(p.x), acc.y.max(p.y))
+        });
+
+        let mut result = String::new();
+        for y in (min.y..=max.y).rev() {
+            for x in min.x..=max.x {
+                let p = Point::new(x, y);
+                let c = if p == self.start {
+                    's'
+                } else if visited.contains(&p) {
+                    '#'
+                } else {
+                    '.'
+                };
+                result.push(c);
+            }
+            result.push('\n');
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::majcn::point::Direction;
+
+    fn run(rope: &mut Rope, commands: &str) {
+        for line in commands.lines() {
+            let (direction, steps) = line.split_once(' ').unwrap();
+            let direction = direction.parse::<Direction>().unwrap();
+            rope.move_by(direction.offset(), steps.parse().unwrap());
+        }
+    }
+
+    const SMALL: &str = "R 4\nU 4\nL 3\nD 1\nR 4\nD 1\nL 5\nR 2";
+
+    #[test]
+    fn test_example() {
+        let mut rope = Rope::new(2);
+        run(&mut rope, SMALL);
+        assert_eq!(rope.tail_visited().len(), 13);
+
+        let expected = "\
+..##.
+...##
+.####
+....#
+s###.
+";
+        assert_eq!(rope.render_visited(1), expected);
+
+        let mut rope = Rope::new(10);
+        run(&mut rope, SMALL);
+        assert_eq!(rope.tail_visited().len(), 1);
+
+        let mut rope = Rope::new(10);
+        run(&mut rope, "R 5\nU 8\nL 8\nD 3\nR 17\nD 10\nL 25\nU 20");
+        assert_eq!(rope.tail_visited().len(), 36);
+        assert_eq!(rope.visited(1).len(), 88);
+    }
+
+    #[test]
+    fn test_render() {
+        let (min, max) = (Point::new(0, 0), Point::new(5, 4));
+
+        let mut rope = Rope::new(2).with_trace();
+        run(&mut rope, "R 4");
+        assert_eq!(
+            rope.render(min, max),
+            "......\n......\n......\n......\ns..TH.\n"
+        );
+
+        let frames = rope.frames();
+        assert_eq!(frames.len(), 5);
+        assert_eq!(frames[0], "H....\n");
+        assert_eq!(frames[2], "sTH..\n");
+
+        let mut rope = Rope::new(10);
+        run(&mut rope, "R 4");
+        assert!(rope.render(min, max).ends_with("4321H.\n"));
+    }
+
+    #[test]
+    fn test_diagonal() {
+        let mut rope = Rope::new(3).with_trace();
+        rope.move_by(Point::new(1, 1), 2);
+        assert_eq!(
+            rope.knots(),
+            &[Point::new(2, 2), Point::new(1, 1), Point::new(0, 0)]
+        );
+
+        rope.step(Point::new(1, -1));
+        assert_eq!(rope.head(), Point::new(3, 1));
+        assert_eq!(rope.knots()[1], Point::new(2, 1));
+        assert_eq!(rope.tail(), Point::new(1, 1));
+        assert_eq!(rope.trace().len(), 4);
+    }
+}
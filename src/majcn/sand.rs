@@ -0,0 +1,269 @@
+use crate::majcn::list::Array2D;
+use crate::majcn::point::Point;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cell {
+    Air,
+    Rock,
+    Sand,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Floor {
+    // sand below the lowest rock falls forever
+    Abyss,
+    // endless rock floor this many rows below the lowest rock
+    Solid(i32),
+}
+
+// y grows downwards, sand tries down, down-left and down-right
+pub struct Cave {
+    grid: Array2D<Cell>,
+    // world coordinates of grid cell (0, 0)
+    origin: Point,
+    source: Point,
+    floor: Floor,
+    // fall path of the previous grain, the next one follows it as far as it can
+    path: Vec<(usize, usize)>,
+    grains: usize,
+    overflowing: bool,
+}
+
+impl Cave {
+    // rock paths are lists of corners connected by horizontal or vertical lines
+    pub fn new(paths: &[Vec<Point>], source: Point, floor: Floor) -> Result<Self, String> {
+        let rocks = paths.iter().flatten();
+        let max_y = rocks.clone().map(|p| p.y).max().unwrap_or(source.y);
+        let top = rocks
+            .clone()
+            .map(|p| p.y)
+            .min()
+            .unwrap_or(source.y)
+            .min(source.y);
+
+        // rows below the grid are either the abyss or the floor
+        let bottom = match floor {
+            Floor::Abyss => max_y + 1,
+            Floor::Solid(distance) if distance > 0 => max_y + distance,
+            Floor::Solid(_) => return Err(String::from("floor has to be below the rocks")),
+        };
+        if source.y >= bottom {
+            return Err(String::from("source is not above the floor"));
+        }
+
+        // sand moves at most one column per row
+        let spread = bottom - source.y;
+        let min_x = rocks.clone().map(|p| p.x).min().unwrap_or(source.x);
+        let max_x = rocks.clone().map(|p| p.x).max().unwrap_or(source.x);
+        let min_x = min_x.min(source.x - spread);
+        let max_x = max_x.max(source.x + spread);
+
+        let width = (max_x - min_x + 1) as usize;
+        let mut grid = Array2D::new(width);
+        for _ in top..bottom {
+            grid.add_line(std::iter::repeat_n(Cell::Air, width));
+        }
+
+        let origin = Point::new(min_x, top);
+        for path in paths {
+            for w in path.windows(2) {
+                let (start, end) = (w[0], w[1]);
+                if start.x != end.x && start.y != end.y {
+                    return Err(format!("diagonal rock line from {start:?} to {end:?}"));
+                }
+
+                let direction = (end - start).signum();
+                let mut p = start;
+                loop {
+                    grid[((p.x - origin.x) as usize, (p.y - origin.y) as usize)] = Cell::Rock;
+                    if p == end {
+                        break;
+                    }
+                    p += direction;
+                }
+            }
+
+            if let [p] = path[..] {
+                grid[((p.x - origin.x) as usize, (p.y - origin.y) as usize)] = Cell::Rock;
+            }
+        }
+
+        Ok(Cave {
+            grid,
+            origin,
+            source,
+            floor,
+            path: vec![],
+            grains: 0,
+            overflowing: false,
+        })
+    }
+
+    pub fn grains(&self) -> usize {
+        self.grains
+    }
+
+    pub fn source(&self) -> Point {
+        self.source
+    }
+
+    pub fn floor(&self) -> Floor {
+        self.floor
+    }
+
+    pub fn cell(&self, p: Point) -> Cell {
+        let (x, y) = (p.x - self.origin.x, p.y - self.origin.y);
+        if y >= 0 && y as usize == self.grid.len() && self.floor != Floor::Abyss {
+            return Cell::Rock;
+        }
+        if x < 0 || y < 0 || x as usize >= self.grid.len_line() || y as usize >= self.grid.len() {
+            return Cell::Air;
+        }
+
+        self.grid[(x as usize, y as usize)]
+    }
+
+    // resting place of the next grain, None once sand falls into the abyss or
+    // the source is covered
+    pub fn drop_grain(&mut self) -> Option<Point> {
+        if self.overflowing {
+            return None;
+        }
+
+        if self.path.is_empty() {
+            let start = (
+                (self.source.x - self.origin.x) as usize,
+                (self.source.y - self.origin.y) as usize,
+            );
+            if self.grid[start] != Cell::Air {
+                return None;
+            }
+            self.path.push(start);
+        }
+
+        while let Some(&(x, y)) = self.path.last() {
+            if y + 1 == self.grid.len() {
+                if self.floor == Floor::Abyss {
+                    self.overflowing = true;
+                    return None;
+                }
+            } else if let Some(next) = [x, x - 1, x + 1]
+                .into_iter()
+                .find(|&nx| self.grid[(nx, y + 1)] == Cell::Air)
+            {
+                self.path.push((next, y + 1));
+                continue;
+            }
+
+            self.path.pop();
+            self.grid[(x, y)] = Cell::Sand;
+            self.grains += 1;
+            return Some(Point::new(x as i32, y as i32) + self.origin);
+        }
+
+        None
+    }
+
+    // drops grains until no more come to rest, returns the number of resting grains
+    pub fn fill(&mut self) -> usize {
+        while self.drop_grain().is_some() {}
+        self.grains
+    }
+
+    // cropped to rocks, sand and the source
+    pub fn render(&self) -> String {
+        let (min_x, max_x) = self
+            .grid
+            .iter_keys()
+            .filter(|&key| self.grid[key] != Cell::Air)
+            .map(|(x, _)| x as i32 + self.origin.x)
+            .fold((self.source.x, self.source.x), |(a, b), x| {
+                (a.min(x), b.max(x))
+            });
+
+        let mut bottom = self.origin.y + self.grid.len() as i32;
+        if let Floor::Solid(_) = self.floor {
+            bottom += 1;
+        }
+
+        let mut result = String::new();
+        for y in self.origin.y..bottom {
+            for x in min_x..=max_x {
+                let p = Point::new(x, y);
+                let c = match self.cell(p) {
+                    Cell::Rock => '#',
+                    Cell::Sand => 'o',
+                    Cell::Air if p == self.source => '+',
+                    Cell::Air => '.',
+                };
+                result.push(c);
+            }
+            result.push('\n');
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Vec<Vec<Point>> {
+        vec![
+            vec![Point::new(498, 4), Point::new(498, 6), Point::new(496, 6)],
+            vec![
+                Point::new(503, 4),
+                Point::new(502, 4),
+                Point::new(502, 9),
+                Point::new(494, 9),
+            ],
+        ]
+    }
+
+    #[test]
+    fn test_abyss() {
+        let mut cave = Cave::new(&example(), Point::new(500, 0), Floor::Abyss).unwrap();
+
+        assert_eq!(cave.drop_grain(), Some(Point::new(500, 8)));
+        assert_eq!(cave.drop_grain(), Some(Point::new(499, 8)));
+        assert_eq!(cave.fill(), 24);
+        assert_eq!(cave.drop_grain(), None);
+
+        let expected = "\
+......+...
+..........
+......o...
+.....ooo..
+....#ooo##
+...o#ooo#.
+..###ooo#.
+....oooo#.
+.o.ooooo#.
+#########.
+";
+        assert_eq!(cave.render(), expected);
+    }
+
+    #[test]
+    fn test_floor() {
+        let mut cave = Cave::new(&example(), Point::new(500, 0), Floor::Solid(2)).unwrap();
+
+        assert_eq!(cave.fill(), 93);
+        assert_eq!(cave.cell(Point::new(500, 0)), Cell::Sand);
+        assert_eq!(cave.cell(Point::new(0, 11)), Cell::Rock);
+        let render = cave.render();
+        assert!(render.lines().last().unwrap().chars().all(|c| c == '#'));
+        assert_eq!(render.lines().count(), 12);
+
+        // custom source and a floor right below the rocks
+        let mut cave = Cave::new(&example(), Point::new(495, 2), Floor::Solid(1)).unwrap();
+        assert_eq!(cave.drop_grain(), Some(Point::new(495, 8)));
+        assert_eq!(cave.fill(), cave.grains());
+        assert_eq!(cave.cell(Point::new(495, 2)), Cell::Sand);
+
+        assert!(Cave::new(&example(), Point::new(500, 0), Floor::Solid(0)).is_err());
+        assert!(Cave::new(&example(), Point::new(500, 20), Floor::Abyss).is_err());
+        let diagonal = vec![vec![Point::new(0, 0), Point::new(1, 1)]];
+        assert!(Cave::new(&diagonal, Point::new(0, -1), Floor::Abyss).is_err());
+    }
+}
@@ -0,0 +1,466 @@
+use std::collections::VecDeque;
+
+use crate::majcn::cycle::CycleDetector;
+use crate::majcn::point::Direction;
+
+// rows are stored bottom first, bit x is set when column x is filled
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub struct Shape {
+    rows: Vec<u64>,
+    width: usize,
+}
+
+impl Shape {
+    // '#' is a cell, '.' is empty, top line first
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let lines = spec.lines().map(str::trim).collect::<Vec<_>>();
+        let width = lines.iter().map(|x| x.len()).max().unwrap_or(0);
+
+        if width == 0 || width > 64 {
+            return Err(String::from("shape has to be between 1 and 64 cells wide"));
+        }
+
+        let mut rows = vec![];
+        for line in lines.iter().rev() {
+            let mut row = 0;
+            for (x, c) in line.chars().enumerate() {
+                match c {
+                    '#' => row |= 1 << x,
+                    '.' => {}
+                    _ => return Err(format!("unknown shape cell: {c}")),
+                }
+            }
+            rows.push(row);
+        }
+
+        let columns = rows.iter().fold(0, |acc, x| acc | x);
+        if rows.contains(&0) || columns != u64::MAX >> (64 - width) {
+            return Err(String::from("shape has empty rows or columns"));
+        }
+
+        Ok(Shape { rows, width })
+    }
+
+    // shapes separated by empty lines
+    pub fn parse_all(spec: &str) -> Result<Vec<Self>, String> {
+        spec.trim().split("\n\n").map(Shape::parse).collect()
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+}
+
+pub trait JetPattern {
+    // only Left and Right push the rock
+    fn next_jet(&mut self) -> Direction;
+
+    // identifies where in the pattern we are, used for periodicity detection
+    fn position(&self) -> usize;
+
+    // number of different positions, bounds the periodicity search
+    fn period(&self) -> usize;
+}
+
+pub struct CyclicJets {
+    jets: Vec<Direction>,
+    index: usize,
+}
+
+impl CyclicJets {
+    pub fn new(jets: Vec<Direction>) -> Self {
+        CyclicJets { jets, index: 0 }
+    }
+
+    pub fn parse(pattern: &str) -> Result<Self, String> {
+        let jets = pattern
+            .trim()
+            .bytes()
+            .map(Direction::try_from)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| String::from("unknown jet"))?;
+
+        if jets.is_empty() {
+            return Err(String::from("empty jet pattern"));
+        }
+
+        Ok(CyclicJets::new(jets))
+    }
+}
+
+impl JetPattern for CyclicJets {
+    fn next_jet(&mut self) -> Direction {
+        let result = self.jets[self.index];
+        self.index = (self.index + 1) % self.jets.len();
+        result
+    }
+
+    fn position(&self) -> usize {
+        self.index
+    }
+
+    fn period(&self) -> usize {
+        self.jets.len()
+    }
+}
+
+// rocks dropped between two trims, batches and snapshots are always trimmed
+const TRIM_INTERVAL: u64 = 32;
+
+// everything that influences future rocks, equal snapshots behave the same
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub struct Snapshot {
+    pub rows: Vec<u64>,
+    pub shape: usize,
+    pub jet: usize,
+}
+
+pub struct Chamber<J> {
+    width: usize,
+    spawn_x: i64,
+    spawn_gap: i64,
+    shapes: Vec<Shape>,
+    next_shape: usize,
+    jets: J,
+    // rows above trimmed, rows below are unreachable and act as solid floor
+    rows: Vec<u64>,
+    trimmed: u64,
+    rocks: u64,
+}
+
+impl<J: JetPattern> Chamber<J> {
+    pub fn new(width: usize, shapes: Vec<Shape>, jets: J) -> Result<Self, String> {
+        if width == 0 || width > 64 {
+            return Err(String::from(
+                "chamber has to be between 1 and 64 cells wide",
+            ));
+        }
+        if shapes.is_empty() {
+            return Err(String::from("no shapes"));
+        }
+        if let Some(i) = shapes.iter().position(|x| x.width > width) {
+            return Err(format!("shape {i} is wider than the chamber"));
+        }
+
+        // two cells from the left wall, closer when the widest shape would not fit
+        let widest = shapes.iter().map(|x| x.width).max().unwrap();
+
+        Ok(Chamber {
+            width,
+            spawn_x: 2.min(width - widest) as i64,
+            spawn_gap: 3,
+            shapes,
+            next_shape: 0,
+            jets,
+            rows: vec![],
+            trimmed: 0,
+            rocks: 0,
+        })
+    }
+
+    // distance of a new rock from the left wall and from the highest rock
+    pub fn with_spawn(mut self, x: usize, gap: usize) -> Result<Self, String> {
+        if let Some(i) = self.shapes.iter().position(|s| x + s.width > self.width) {
+            return Err(format!(
+                "shape {i} does not fit next to the left wall at {x}"
+            ));
+        }
+
+        self.spawn_x = x as i64;
+        self.spawn_gap = gap as i64;
+        Ok(self)
+    }
+
+    pub fn height(&self) -> u64 {
+        self.trimmed + self.rows.len() as u64
+    }
+
+    pub fn rocks(&self) -> u64 {
+        self.rocks
+    }
+
+    // number of rows kept in memory
+    pub fn stored_rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    fn collides(&self, shape: &Shape, x: i64, y: i64) -> bool {
+        if x < 0 || x as usize + shape.width > self.width || y < self.trimmed as i64 {
+            return true;
+        }
+
+        shape.rows.iter().enumerate().any(|(i, row)| {
+            let index = (y - self.trimmed as i64) as usize + i;
+            self.rows.get(index).is_some_and(|x2| x2 & (row << x) != 0)
+        })
+    }
+
+    pub fn drop_rock(&mut self) {
+        let shape_index = self.next_shape;
+        self.next_shape = (self.next_shape + 1) % self.shapes.len();
+
+        let shape = &self.shapes[shape_index];
+        let mut x = self.spawn_x;
+        let mut y = self.height() as i64 + self.spawn_gap;
+
+        loop {
+            let nx = match self.jets.next_jet() {
+                Direction::Left => x - 1,
+                Direction::Right => x + 1,
+                _ => x,
+            };
+            if !self.collides(shape, nx, y) {
+                x = nx;
+            }
+
+            if self.collides(shape, x, y - 1) {
+                break;
+            }
+            y -= 1;
+        }
+
+        let base = (y - self.trimmed as i64) as usize;
+        let needed = base + shape.rows.len();
+        if self.rows.len() < needed {
+            self.rows.resize(needed, 0);
+        }
+        for (i, row) in shape.rows.iter().enumerate() {
+            self.rows[base + i] |= row << x;
+        }
+
+        self.rocks += 1;
+        if self.rocks.is_multiple_of(TRIM_INTERVAL) {
+            self.trim();
+        }
+    }
+
+    pub fn drop_rocks(&mut self, n: u64) {
+        for _ in 0..n {
+            self.drop_rock();
+        }
+        self.trim();
+    }
+
+    // removes rows that no falling cell can reach from above
+    fn trim(&mut self) {
+        let lowest = self.unreachable_rows();
+        if lowest > 0 {
+            self.rows.drain(..lowest);
+            self.trimmed += lowest as u64;
+        }
+    }
+
+    // number of stored rows at the bottom that no falling cell can reach
+    fn unreachable_rows(&self) -> usize {
+        let height = self.rows.len();
+        let full = if self.width == 64 {
+            u64::MAX
+        } else {
+            (1 << self.width) - 1
+        };
+
+        let mut reached = vec![0_u64; height + 1];
+        reached[height] = full;
+        let mut queue = (0..self.width)
+            .map(|x| (x, height))
+            .collect::<VecDeque<_>>();
+
+        let mut lowest = height;
+        while let Some((x, y)) = queue.pop_front() {
+            lowest = lowest.min(y);
+
+            let mut candidates = vec![];
+            if x > 0 {
+                candidates.push((x - 1, y));
+            }
+            if x + 1 < self.width {
+                candidates.push((x + 1, y));
+            }
+            if y > 0 {
+                candidates.push((x, y - 1));
+            }
+
+            for (nx, ny) in candidates {
+                if reached[ny] & (1 << nx) == 0 && self.rows[ny] & (1 << nx) == 0 {
+                    reached[ny] |= 1 << nx;
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+
+        lowest
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            rows: self.rows[self.unreachable_rows()..].to_vec(),
+            shape: self.next_shape,
+            jet: self.jets.position(),
+        }
+    }
+
+    // top row first, trimmed history is drawn as a wavy floor
+    pub fn render(&self) -> String {
+        let mut result = String::new();
+        for row in self.rows.iter().rev() {
+            result.push('|');
+            result.extend((0..self.width).map(|x| if row & (1 << x) != 0 { '#' } else { '.' }));
+            result.push_str("|\n");
+        }
+
+        let floor = if self.trimmed == 0 { '-' } else { '~' };
+        result.push('+');
+        result.extend(std::iter::repeat_n(floor, self.width));
+        result.push_str("+\n");
+
+        result
+    }
+
+    // height after n more rocks, skips repeated periods when the state starts to repeat
+    pub fn height_after(&mut self, n: u64) -> u64 {
+        // one pass over every shape and jet pair for the surface to settle and one for the
+        // period to show up, rocks past that are simulated without keeping snapshots
+        let limit = 2 * self.shapes.len() as u64 * self.jets.period() as u64;
+
+        let mut detector = CycleDetector::new();
+        detector.observe(0, self.snapshot());
+
+        let mut heights = vec![self.height()];
+        for step in 1..=n.min(limit) as usize {
+            self.drop_rock();
+            heights.push(self.height());
+
+            if let Some(cycle) = detector.observe(step, self.snapshot()) {
+                return cycle.extrapolate(&heights, n as usize);
+            }
+        }
+
+        self.drop_rocks(n.saturating_sub(limit));
+        self.height()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHAPES: &str = "
+####
+
+.#.
+###
+.#.
+
+..#
+..#
+###
+
+#
+#
+#
+#
+
+##
+##
+";
+
+    const JETS: &str = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
+
+    fn chamber(width: usize) -> Chamber<CyclicJets> {
+        Chamber::new(
+            width,
+            Shape::parse_all(SHAPES).unwrap(),
+            CyclicJets::parse(JETS).unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_example() {
+        let mut c = chamber(7);
+        c.drop_rocks(2022);
+        assert_eq!(c.height(), 3068);
+        assert!(c.stored_rows() < 100);
+
+        assert_eq!(chamber(7).height_after(2022), 3068);
+        assert_eq!(chamber(7).height_after(1000000000000), 1514285714288);
+    }
+
+    #[test]
+    fn test_render() {
+        let mut c = chamber(7);
+        c.drop_rocks(3);
+
+        let expected = "\
+|..#....|
+|..#....|
+|####...|
+|..###..|
+|...#...|
+|..####.|
++-------+
+";
+        assert_eq!(c.render(), expected);
+        assert_eq!(c.snapshot().shape, 3);
+    }
+
+    #[test]
+    fn test_custom_chamber() {
+        let shapes = Shape::parse_all("###\n\n#").unwrap();
+        let jets = CyclicJets::parse("<").unwrap();
+        let mut c = Chamber::new(3, shapes, jets)
+            .unwrap()
+            .with_spawn(0, 1)
+            .unwrap();
+
+        c.drop_rocks(2);
+        assert_eq!(c.height(), 2);
+        assert_eq!(c.stored_rows(), 1);
+        assert_eq!(c.render(), "|#..|\n+~~~+\n");
+
+        let mut c = chamber(9);
+        let height = c.height_after(5000);
+        c = chamber(9);
+        c.drop_rocks(5000);
+        assert_eq!(height, c.height());
+
+        // every jet is new, so the state never repeats
+        struct Endless(usize);
+        impl JetPattern for Endless {
+            fn next_jet(&mut self) -> Direction {
+                self.0 += 1;
+                Direction::Left
+            }
+            fn position(&self) -> usize {
+                self.0
+            }
+            fn period(&self) -> usize {
+                1
+            }
+        }
+
+        let shapes = Shape::parse_all(SHAPES).unwrap();
+        let mut c = Chamber::new(7, shapes.clone(), Endless(0)).unwrap();
+        let height = c.height_after(1000);
+        assert_eq!(c.rocks(), 1000);
+        let mut c = Chamber::new(7, shapes.clone(), Endless(0)).unwrap();
+        c.drop_rocks(1000);
+        assert_eq!(height, c.height());
+
+        let jets = || CyclicJets::parse(JETS).unwrap();
+        assert!(Chamber::new(0, shapes.clone(), jets()).is_err());
+        assert!(Chamber::new(65, shapes.clone(), jets()).is_err());
+        assert!(Chamber::new(7, vec![], jets()).is_err());
+        assert!(Chamber::new(3, shapes.clone(), jets()).is_err());
+        assert!(Chamber::new(4, shapes.clone(), jets()).is_ok());
+        assert!(chamber(7).with_spawn(4, 3).is_err());
+
+        assert!(Shape::parse(".#.\n...").is_err());
+        assert!(Shape::parse(".#\n.#").is_err());
+        assert!(Shape::parse("#.\n#.").is_err());
+    }
+}
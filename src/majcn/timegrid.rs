@@ -0,0 +1,300 @@
+use std::collections::HashSet;
+use std::collections::VecDeque;
+
+use crate::majcn::num::lcm;
+use crate::majcn::point::Direction;
+use crate::majcn::point::Point;
+
+pub trait Obstacles {
+    // is_blocked(p, t) == is_blocked(p, t + period)
+    fn period(&self) -> usize;
+
+    fn is_blocked(&self, p: Point, time: usize) -> bool;
+}
+
+impl<T: Obstacles> Obstacles for &T {
+    fn period(&self) -> usize {
+        (*self).period()
+    }
+
+    fn is_blocked(&self, p: Point, time: usize) -> bool {
+        (*self).is_blocked(p, time)
+    }
+}
+
+// obstacles moving one cell per minute in a fixed direction, wrapping around
+// inside a rectangle, y is the line index
+pub struct LinearObstacles {
+    min: Point,
+    max: Point,
+    obstacles: HashSet<(Point, Direction)>,
+}
+
+impl LinearObstacles {
+    // both corners are inclusive
+    pub fn new(min: Point, max: Point) -> Self {
+        assert!(min.x <= max.x && min.y <= max.y, "empty rectangle!");
+
+        LinearObstacles {
+            min,
+            max,
+            obstacles: HashSet::new(),
+        }
+    }
+
+    pub fn add(&mut self, p: Point, direction: Direction) {
+        assert!(self.contains(p), "obstacle outside of the rectangle!");
+        self.obstacles.insert((p, direction));
+    }
+
+    pub fn width(&self) -> usize {
+        (self.max.x - self.min.x + 1) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max.y - self.min.y + 1) as usize
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        p.x >= self.min.x && p.x <= self.max.x && p.y >= self.min.y && p.y <= self.max.y
+    }
+
+    pub fn len(&self) -> usize {
+        self.obstacles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.obstacles.is_empty()
+    }
+}
+
+impl Obstacles for LinearObstacles {
+    fn period(&self) -> usize {
+        lcm(self.width(), self.height())
+    }
+
+    fn is_blocked(&self, p: Point, time: usize) -> bool {
+        if !self.contains(p) {
+            return false;
+        }
+
+        let (w, h) = (self.width() as i64, self.height() as i64);
+        let t = time as i64;
+
+        // where an obstacle had to start to be at p after time minutes
+        Direction::ALL.into_iter().any(|direction| {
+            let offset = direction.screen_offset();
+            let x = (p.x - self.min.x) as i64 - offset.x as i64 * t;
+            let y = (p.y - self.min.y) as i64 - offset.y as i64 * t;
+            let origin = Point::new(
+                self.min.x + x.rem_euclid(w) as i32,
+                self.min.y + y.rem_euclid(h) as i32,
+            );
+            self.obstacles.contains(&(origin, direction))
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path {
+    pub start_time: usize,
+    // position at start_time + i
+    pub positions: Vec<Point>,
+    // arrival time at every waypoint after the first one
+    pub arrivals: Vec<usize>,
+}
+
+impl Path {
+    pub fn duration(&self) -> usize {
+        self.positions.len() - 1
+    }
+
+    pub fn end_time(&self) -> usize {
+        self.start_time + self.duration()
+    }
+}
+
+pub struct TimeGrid<O, W> {
+    obstacles: O,
+    walkable: W,
+    moves: Vec<Point>,
+}
+
+impl<O, W> TimeGrid<O, W>
+where
+    O: Obstacles,
+    W: Fn(Point) -> bool,
+{
+    // walkable describes the static terrain, by default we can wait or move in 4 directions
+    pub fn new(obstacles: O, walkable: W) -> Self {
+        let mut moves = vec![Point::new(0, 0)];
+        moves.extend(Direction::ALL.map(Direction::screen_offset));
+
+        TimeGrid {
+            obstacles,
+            walkable,
+            moves,
+        }
+    }
+
+    pub fn with_moves(mut self, moves: &[Point]) -> Self {
+        self.moves = moves.to_vec();
+        self
+    }
+
+    pub fn obstacles(&self) -> &O {
+        &self.obstacles
+    }
+
+    pub fn shortest_path(&self, start: Point, goal: Point, start_time: usize) -> Option<Path> {
+        self.trip(&[start, goal], start_time)
+    }
+
+    // visits waypoints in order, the search runs over all legs at once so an
+    // early arrival never hides a better continuation
+    pub fn trip(&self, waypoints: &[Point], start_time: usize) -> Option<Path> {
+        let (&start, _) = waypoints.split_first()?;
+        let period = self.obstacles.period().max(1);
+
+        if !(self.walkable)(start) || self.obstacles.is_blocked(start, start_time) {
+            return None;
+        }
+
+        // position, leg and parent index of every reached state
+        let mut nodes = vec![(start, 1, usize::MAX)];
+        let mut visited = HashSet::from([(start, start_time % period, 1)]);
+        let mut queue = VecDeque::from([(0, start_time)]);
+
+        let last = loop {
+            let (index, time) = queue.pop_front()?;
+            let (p, leg, _) = nodes[index];
+            if leg == waypoints.len() {
+                break index;
+            }
+
+            let time = time + 1;
+            for &offset in self.moves.iter() {
+                let np = p + offset;
+                if !(self.walkable)(np) || self.obstacles.is_blocked(np, time) {
+                    continue;
+                }
+
+                let nleg = if np == waypoints[leg] { leg + 1 } else { leg };
+                if visited.insert((np, time % period, nleg)) {
+                    nodes.push((np, nleg, index));
+                    queue.push_back((nodes.len() - 1, time));
+                }
+            }
+        };
+
+        let mut chain = vec![];
+        let mut index = last;
+        while index != usize::MAX {
+            chain.push(nodes[index]);
+            index = nodes[index].2;
+        }
+        chain.reverse();
+
+        let arrivals = chain
+            .windows(2)
+            .enumerate()
+            .filter(|(_, x)| x[0].1 != x[1].1)
+            .map(|(i, _)| start_time + i + 1)
+            .collect();
+
+        Some(Path {
+            start_time,
+            positions: chain.into_iter().map(|x| x.0).collect(),
+            arrivals,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+#.######
+#>>.<^<#
+#.<..<<#
+#>v.><>#
+#<^v^^>#
+######.#";
+
+    fn parse(input: &str) -> (LinearObstacles, Point, Point) {
+        let lines = input.lines().collect::<Vec<_>>();
+        let (w, h) = (lines[0].len() as i32, lines.len() as i32);
+
+        let mut obstacles = LinearObstacles::new(Point::new(1, 1), Point::new(w - 2, h - 2));
+        for (y, line) in lines.iter().enumerate() {
+            for (x, c) in line.bytes().enumerate() {
+                if let Ok(direction) = Direction::try_from(c) {
+                    obstacles.add(Point::new(x as i32, y as i32), direction);
+                }
+            }
+        }
+
+        (obstacles, Point::new(1, 0), Point::new(w - 2, h - 1))
+    }
+
+    #[test]
+    fn test_obstacles() {
+        let (obstacles, _, _) = parse(EXAMPLE);
+
+        assert_eq!(obstacles.period(), 12);
+        assert_eq!(obstacles.len(), 19);
+        assert!(obstacles.is_blocked(Point::new(1, 1), 0));
+        assert!(!obstacles.is_blocked(Point::new(1, 1), 1));
+        assert!(obstacles.is_blocked(Point::new(3, 1), 1));
+        assert!(!obstacles.is_blocked(Point::new(1, 0), 5));
+
+        for t in 0..12 {
+            for y in 1..=4 {
+                for x in 1..=6 {
+                    let p = Point::new(x, y);
+                    assert_eq!(obstacles.is_blocked(p, t), obstacles.is_blocked(p, t + 12));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_trip() {
+        let (obstacles, start, end) = parse(EXAMPLE);
+        let walkable = |p: Point| p == start || p == end || obstacles.contains(p);
+        let grid = TimeGrid::new(&obstacles, walkable);
+
+        let path = grid.shortest_path(start, end, 0).unwrap();
+        assert_eq!(path.duration(), 18);
+        assert_eq!(path.arrivals, vec![18]);
+
+        let path = grid.trip(&[start, end, start, end], 0).unwrap();
+        assert_eq!(path.end_time(), 54);
+        // legs are optimized together, only the final arrival is fixed
+        assert_eq!(path.arrivals.len(), 3);
+        assert_eq!(path.arrivals[2], 54);
+        assert!(path.arrivals[0] >= 18);
+
+        // every step is a legal move onto a free cell
+        for (i, x) in path.positions.windows(2).enumerate() {
+            assert!(x[0].manhattan(x[1]) <= 1);
+            assert!(!obstacles.is_blocked(x[1], i + 1));
+        }
+    }
+
+    #[test]
+    fn test_unreachable() {
+        let mut obstacles = LinearObstacles::new(Point::new(0, 0), Point::new(2, 0));
+        obstacles.add(Point::new(0, 0), Direction::Right);
+        obstacles.add(Point::new(1, 0), Direction::Right);
+        obstacles.add(Point::new(2, 0), Direction::Right);
+
+        let grid = TimeGrid::new(obstacles, |p: Point| p.y == 0 && (-1..=3).contains(&p.x));
+        assert_eq!(
+            grid.shortest_path(Point::new(-1, 0), Point::new(3, 0), 0),
+            None
+        );
+        assert_eq!(grid.trip(&[], 0), None);
+    }
+}
@@ -0,0 +1,365 @@
+struct TreapNode<T> {
+    value: Option<T>,
+    priority: u64,
+    size: usize,
+    left: Option<usize>,
+    right: Option<usize>,
+    parent: Option<usize>,
+}
+
+// sequence ordered by position, handles stay valid until the element is removed
+pub struct ImplicitTreap<T> {
+    nodes: Vec<TreapNode<T>>,
+    available_slots: Vec<usize>,
+    root: Option<usize>,
+    seed: u64,
+}
+
+impl<T> ImplicitTreap<T> {
+    pub fn new() -> Self {
+        ImplicitTreap {
+            nodes: vec![],
+            available_slots: vec![],
+            root: None,
+            seed: 0x2545F4914F6CDD1D,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.size(self.root)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    pub fn get(&self, handle: usize) -> Option<&T> {
+        self.nodes.get(handle).and_then(|x| x.value.as_ref())
+    }
+
+    pub fn get_mut(&mut self, handle: usize) -> Option<&mut T> {
+        self.nodes.get_mut(handle).and_then(|x| x.value.as_mut())
+    }
+
+    pub fn push_back(&mut self, value: T) -> usize {
+        self.insert_at(self.len(), value)
+    }
+
+    pub fn insert_at(&mut self, index: usize, value: T) -> usize {
+        let handle = self.allocate(value);
+        self.attach_at(index, handle);
+        handle
+    }
+
+    pub fn remove_at(&mut self, index: usize) -> T {
+        let handle = self.detach_at(index);
+        self.available_slots.push(handle);
+        self.nodes[handle].value.take().unwrap()
+    }
+
+    pub fn remove(&mut self, handle: usize) -> T {
+        self.remove_at(self.position_of(handle))
+    }
+
+    // the element keeps its handle
+    pub fn move_to(&mut self, handle: usize, index: usize) {
+        let detached = self.detach_at(self.position_of(handle));
+        self.attach_at(index, detached);
+    }
+
+    pub fn position_of(&self, handle: usize) -> usize {
+        let mut result = self.size(self.nodes[handle].left);
+
+        let mut node = handle;
+        while let Some(parent) = self.nodes[node].parent {
+            if self.nodes[parent].right == Some(node) {
+                result += self.size(self.nodes[parent].left) + 1;
+            }
+            node = parent;
+        }
+
+        result
+    }
+
+    pub fn handle_at(&self, index: usize) -> usize {
+        assert!(index < self.len(), "index out of bounds!");
+
+        let mut node = self.root.unwrap();
+        let mut index = index;
+        loop {
+            let left_size = self.size(self.nodes[node].left);
+            match index.cmp(&left_size) {
+                std::cmp::Ordering::Less => node = self.nodes[node].left.unwrap(),
+                std::cmp::Ordering::Equal => return node,
+                std::cmp::Ordering::Greater => {
+                    index -= left_size + 1;
+                    node = self.nodes[node].right.unwrap();
+                }
+            }
+        }
+    }
+
+    pub fn get_at(&self, index: usize) -> &T {
+        &self[self.handle_at(index)]
+    }
+
+    // first k elements are moved to the end
+    pub fn rotate_left(&mut self, k: usize) {
+        if self.is_empty() {
+            return;
+        }
+
+        let k = k % self.len();
+        let (left, right) = self.split(self.root, k);
+        self.root = self.merge(right, left);
+        self.set_root_parent();
+    }
+
+    pub fn rotate_right(&mut self, k: usize) {
+        if self.is_empty() {
+            return;
+        }
+
+        let k = k % self.len();
+        self.rotate_left(self.len() - k);
+    }
+
+    pub fn iter(&self) -> ImplicitTreapIter<'_, T> {
+        let mut result = ImplicitTreapIter {
+            treap: self,
+            stack: vec![],
+        };
+        result.push_left(self.root);
+        result
+    }
+
+    fn next_priority(&mut self) -> u64 {
+        // xorshift64*
+        self.seed ^= self.seed >> 12;
+        self.seed ^= self.seed << 25;
+        self.seed ^= self.seed >> 27;
+        self.seed.wrapping_mul(0x2545F4914F6CDD1D)
+    }
+
+    fn allocate(&mut self, value: T) -> usize {
+        let node = TreapNode {
+            value: Some(value),
+            priority: self.next_priority(),
+            size: 1,
+            left: None,
+            right: None,
+            parent: None,
+        };
+
+        match self.available_slots.pop() {
+            Some(slot) => {
+                self.nodes[slot] = node;
+                slot
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn attach_at(&mut self, index: usize, handle: usize) {
+        assert!(index <= self.len(), "index out of bounds!");
+
+        let node = &mut self.nodes[handle];
+        node.size = 1;
+        node.left = None;
+        node.right = None;
+        node.parent = None;
+
+        let (left, right) = self.split(self.root, index);
+        let left = self.merge(left, Some(handle));
+        self.root = self.merge(left, right);
+        self.set_root_parent();
+    }
+
+    fn detach_at(&mut self, index: usize) -> usize {
+        assert!(index < self.len(), "index out of bounds!");
+
+        let (left, rest) = self.split(self.root, index);
+        let (middle, right) = self.split(rest, 1);
+        self.root = self.merge(left, right);
+        self.set_root_parent();
+
+        middle.unwrap()
+    }
+
+    #[inline]
+    fn size(&self, node: Option<usize>) -> usize {
+        node.map_or(0, |x| self.nodes[x].size)
+    }
+
+    fn update(&mut self, node: usize) {
+        let TreapNode { left, right, .. } = self.nodes[node];
+        self.nodes[node].size = 1 + self.size(left) + self.size(right);
+
+        for child in [left, right].into_iter().flatten() {
+            self.nodes[child].parent = Some(node);
+        }
+    }
+
+    fn set_root_parent(&mut self) {
+        if let Some(root) = self.root {
+            self.nodes[root].parent = None;
+        }
+    }
+
+    // first k elements go to the left part
+    fn split(&mut self, node: Option<usize>, k: usize) -> (Option<usize>, Option<usize>) {
+        let Some(node) = node else {
+            return (None, None);
+        };
+
+        let left_size = self.size(self.nodes[node].left);
+        if k <= left_size {
+            let (l, r) = self.split(self.nodes[node].left, k);
+            self.nodes[node].left = r;
+            self.update(node);
+            if let Some(l) = l {
+                self.nodes[l].parent = None;
+            }
+            (l, Some(node))
+        } else {
+            let (l, r) = self.split(self.nodes[node].right, k - left_size - 1);
+            self.nodes[node].right = l;
+            self.update(node);
+            if let Some(r) = r {
+                self.nodes[r].parent = None;
+            }
+            (Some(node), r)
+        }
+    }
+
+    fn merge(&mut self, left: Option<usize>, right: Option<usize>) -> Option<usize> {
+        match (left, right) {
+            (None, x) | (x, None) => x,
+            (Some(l), Some(r)) => {
+                if self.nodes[l].priority > self.nodes[r].priority {
+                    let merged = self.merge(self.nodes[l].right, Some(r));
+                    self.nodes[l].right = merged;
+                    self.update(l);
+                    Some(l)
+                } else {
+                    let merged = self.merge(Some(l), self.nodes[r].left);
+                    self.nodes[r].left = merged;
+                    self.update(r);
+                    Some(r)
+                }
+            }
+        }
+    }
+}
+
+impl<T> Default for ImplicitTreap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FromIterator<T> for ImplicitTreap<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut result = ImplicitTreap::new();
+        for value in iter {
+            result.push_back(value);
+        }
+        result
+    }
+}
+
+impl<T> std::ops::Index<usize> for ImplicitTreap<T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).unwrap()
+    }
+}
+
+impl<T> std::ops::IndexMut<usize> for ImplicitTreap<T> {
+    #[inline]
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.get_mut(index).unwrap()
+    }
+}
+
+pub struct ImplicitTreapIter<'a, T> {
+    treap: &'a ImplicitTreap<T>,
+    stack: Vec<usize>,
+}
+
+impl<T> ImplicitTreapIter<'_, T> {
+    fn push_left(&mut self, node: Option<usize>) {
+        let mut node = node;
+        while let Some(x) = node {
+            self.stack.push(x);
+            node = self.treap.nodes[x].left;
+        }
+    }
+}
+
+impl<'a, T> Iterator for ImplicitTreapIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.push_left(self.treap.nodes[node].right);
+        self.treap.nodes[node].value.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mix(data: &[i64], rounds: usize) -> Vec<i64> {
+        let mut treap = data.iter().copied().collect::<ImplicitTreap<_>>();
+        let n = data.len() as i64;
+
+        for _ in 0..rounds {
+            for (handle, value) in data.iter().enumerate() {
+                let position = treap.position_of(handle) as i64;
+                treap.move_to(handle, (position + value).rem_euclid(n - 1) as usize);
+            }
+        }
+
+        let zero = data.iter().position(|x| x == &0).unwrap();
+        treap.rotate_left(treap.position_of(zero));
+        treap.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_mixing() {
+        let data = [1, 2, -3, 3, -2, 0, 4];
+        assert_eq!(mix(&data, 1), vec![0, 3, -2, 1, 2, -3, 4]);
+
+        let data = data.map(|x| x * 811589153);
+        let result = mix(&data, 10);
+        assert_eq!(
+            result[1000 % 7] + result[2000 % 7] + result[3000 % 7],
+            1623178306
+        );
+    }
+
+    #[test]
+    fn test_positions() {
+        let mut treap = (0..100).collect::<ImplicitTreap<_>>();
+
+        assert!((0..100).all(|i| treap.position_of(i) == i));
+        assert_eq!(treap.remove_at(10), 10);
+        assert_eq!(*treap.get_at(10), 11);
+
+        let handle = treap.insert_at(0, 1000);
+        assert_eq!(treap.position_of(handle), 0);
+        assert_eq!(treap.position_of(99), 99);
+
+        treap.rotate_right(1);
+        assert_eq!(*treap.get_at(0), 99);
+        assert_eq!(treap.position_of(handle), 1);
+        assert_eq!(treap.len(), 100);
+    }
+}
@@ -0,0 +1,356 @@
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand {
+    Register(usize),
+    Value(i64),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct State {
+    pub registers: Vec<i64>,
+    // index of the next instruction, effects see it already advanced past the current one
+    pub pc: usize,
+    pub cycle: u64,
+    // instruction which tried to jump outside of the program
+    pub invalid_jump: Option<usize>,
+}
+
+impl State {
+    #[inline]
+    pub fn get(&self, operand: &Operand) -> i64 {
+        match operand {
+            Operand::Register(r) => self.registers[*r],
+            Operand::Value(v) => *v,
+        }
+    }
+
+    #[inline]
+    pub fn set(&mut self, operand: &Operand, value: i64) {
+        match operand {
+            Operand::Register(r) => self.registers[*r] = value,
+            Operand::Value(_) => panic!("can not write into a constant!"),
+        }
+    }
+
+    // relative to the current instruction, like jnz in most puzzles
+    pub fn jump(&mut self, offset: i64) {
+        let current = self.pc - 1;
+        match isize::try_from(offset)
+            .ok()
+            .and_then(|x| current.checked_add_signed(x))
+        {
+            Some(pc) => self.pc = pc,
+            None => self.invalid_jump = Some(current),
+        }
+    }
+}
+
+pub type Effect = fn(&mut State, &[Operand]);
+
+pub struct InstructionDef {
+    pub name: &'static str,
+    pub arity: usize,
+    pub cycles: u32,
+    pub effect: Effect,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    pub def: usize,
+    pub operands: Vec<Operand>,
+}
+
+pub struct InstructionSet {
+    registers: Vec<&'static str>,
+    defs: Vec<InstructionDef>,
+}
+
+impl InstructionSet {
+    pub fn new(registers: &[&'static str]) -> Self {
+        InstructionSet {
+            registers: registers.to_vec(),
+            defs: vec![],
+        }
+    }
+
+    pub fn instruction(
+        mut self,
+        name: &'static str,
+        arity: usize,
+        cycles: u32,
+        effect: Effect,
+    ) -> Self {
+        assert!(
+            cycles > 0,
+            "instruction {name} has to take at least one cycle!"
+        );
+
+        self.defs.push(InstructionDef {
+            name,
+            arity,
+            cycles,
+            effect,
+        });
+        self
+    }
+
+    pub fn register(&self, name: &str) -> Option<usize> {
+        self.registers.iter().position(|x| *x == name)
+    }
+
+    pub fn parse_operand(&self, s: &str) -> Result<Operand, String> {
+        if let Some(r) = self.register(s) {
+            return Ok(Operand::Register(r));
+        }
+
+        s.parse()
+            .map(Operand::Value)
+            .map_err(|_| format!("unknown operand: {s}"))
+    }
+
+    pub fn parse_line(&self, line: &str) -> Result<Instruction, String> {
+        let mut parts = line.split_whitespace();
+        let name = parts.next().ok_or("empty line")?;
+
+        let def = self
+            .defs
+            .iter()
+            .position(|x| x.name == name)
+            .ok_or_else(|| format!("unknown instruction: {name}"))?;
+
+        let operands = parts
+            .map(|x| self.parse_operand(x.trim_end_matches(',')))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if operands.len() != self.defs[def].arity {
+            return Err(format!(
+                "{name} expects {} operands, got {}",
+                self.defs[def].arity,
+                operands.len()
+            ));
+        }
+
+        Ok(Instruction { def, operands })
+    }
+
+    pub fn parse(&self, program: &str) -> Result<Vec<Instruction>, String> {
+        program.lines().map(|x| self.parse_line(x)).collect()
+    }
+
+    pub fn format(&self, instruction: &Instruction) -> String {
+        let mut result = String::from(self.defs[instruction.def].name);
+        for operand in instruction.operands.iter() {
+            result.push(' ');
+            match operand {
+                Operand::Register(r) => result.push_str(self.registers[*r]),
+                Operand::Value(v) => result.push_str(&v.to_string()),
+            }
+        }
+        result
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    Halted,
+    Breakpoint(usize),
+    // jump before the first instruction, the vm stays stopped
+    InvalidJump(usize),
+}
+
+pub struct Vm<'a> {
+    set: &'a InstructionSet,
+    program: Vec<Instruction>,
+    state: State,
+    remaining_cycles: u32,
+    breakpoints: HashSet<usize>,
+    ignore_breakpoint: bool,
+    trace: Option<Vec<String>>,
+}
+
+impl<'a> Vm<'a> {
+    pub fn new(set: &'a InstructionSet, program: Vec<Instruction>) -> Self {
+        Vm {
+            set,
+            program,
+            state: State {
+                registers: vec![0; set.registers.len()],
+                pc: 0,
+                cycle: 0,
+                invalid_jump: None,
+            },
+            remaining_cycles: 0,
+            breakpoints: HashSet::new(),
+            ignore_breakpoint: false,
+            trace: None,
+        }
+    }
+
+    pub fn with_register(mut self, name: &str, value: i64) -> Self {
+        let r = self.set.register(name).expect("unknown register");
+        self.state.registers[r] = value;
+        self
+    }
+
+    pub fn with_trace(mut self) -> Self {
+        self.trace = Some(vec![]);
+        self
+    }
+
+    pub fn add_breakpoint(&mut self, pc: usize) {
+        self.breakpoints.insert(pc);
+    }
+
+    pub fn remove_breakpoint(&mut self, pc: usize) {
+        self.breakpoints.remove(&pc);
+    }
+
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    pub fn register(&self, name: &str) -> i64 {
+        self.state.registers[self.set.register(name).expect("unknown register")]
+    }
+
+    pub fn trace(&self) -> &[String] {
+        self.trace.as_deref().unwrap_or_default()
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.remaining_cycles == 0 && self.state.pc >= self.program.len()
+    }
+
+    // observer is called during every cycle (1-based), before the cycle's effects are applied
+    pub fn run<F>(&mut self, mut observer: F) -> StopReason
+    where
+        F: FnMut(u64, &State),
+    {
+        loop {
+            if let Some(pc) = self.state.invalid_jump {
+                return StopReason::InvalidJump(pc);
+            }
+
+            if self.is_halted() {
+                return StopReason::Halted;
+            }
+
+            if self.remaining_cycles == 0 {
+                let pc = self.state.pc;
+                if self.breakpoints.contains(&pc) && !self.ignore_breakpoint {
+                    self.ignore_breakpoint = true;
+                    return StopReason::Breakpoint(pc);
+                }
+                self.ignore_breakpoint = false;
+                self.remaining_cycles = self.set.defs[self.program[pc].def].cycles;
+            }
+
+            observer(self.state.cycle + 1, &self.state);
+            self.step_cycle();
+        }
+    }
+
+    pub fn run_to_end(&mut self) -> StopReason {
+        self.run(|_, _| {})
+    }
+
+    fn step_cycle(&mut self) {
+        self.state.cycle += 1;
+        self.remaining_cycles -= 1;
+
+        if self.remaining_cycles > 0 {
+            return;
+        }
+
+        let pc = self.state.pc;
+        let instruction = &self.program[pc];
+        let def = &self.set.defs[instruction.def];
+
+        self.state.pc += 1;
+        (def.effect)(&mut self.state, &instruction.operands);
+
+        if let Some(trace) = self.trace.as_mut() {
+            trace.push(format!(
+                "{:>6} {:>4}: {:<16} {:?}",
+                self.state.cycle,
+                pc,
+                self.set.format(instruction),
+                self.state.registers
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jumps_and_breakpoints() {
+        let set = InstructionSet::new(&["a", "b"])
+            .instruction("inc", 1, 1, |s, o| s.set(&o[0], s.get(&o[0]) + 1))
+            .instruction("dec", 1, 1, |s, o| s.set(&o[0], s.get(&o[0]) - 1))
+            .instruction("jnz", 2, 2, |s, o| {
+                if s.get(&o[0]) != 0 {
+                    s.jump(s.get(&o[1]))
+                }
+            });
+
+        let program = set.parse("inc a\ndec b\njnz b, -2\ninc a").unwrap();
+        let mut vm = Vm::new(&set, program).with_register("b", 3).with_trace();
+
+        vm.add_breakpoint(3);
+        assert_eq!(vm.run_to_end(), StopReason::Breakpoint(3));
+        assert_eq!(vm.register("a"), 3);
+        assert_eq!(vm.state().cycle, 12);
+
+        let mut observed = vec![];
+        assert_eq!(
+            vm.run(|c, s| observed.push((c, s.registers[0]))),
+            StopReason::Halted
+        );
+        assert_eq!(observed, vec![(13, 3)]);
+        assert_eq!(vm.register("a"), 4);
+        assert_eq!(vm.trace().len(), 10);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let set = InstructionSet::new(&["x"])
+            .instruction("addx", 1, 2, |s, o| s.registers[0] += s.get(&o[0]));
+
+        assert!(set.parse("addx 3").is_ok());
+        assert!(set.parse("addx").is_err());
+        assert!(set.parse("subx 3").is_err());
+        assert!(set.parse("addx y").is_err());
+    }
+
+    #[test]
+    fn test_invalid_jump() {
+        let set = InstructionSet::new(&["a"])
+            .instruction("inc", 1, 1, |s, o| s.set(&o[0], s.get(&o[0]) + 1))
+            .instruction("jmp", 1, 1, |s, o| s.jump(s.get(&o[0])));
+
+        // jumping right past the end halts normally
+        let program = set.parse("inc a\njmp 1\ninc a").unwrap();
+        assert_eq!(Vm::new(&set, program).run_to_end(), StopReason::Halted);
+
+        let program = set.parse("inc a\njmp -2\ninc a").unwrap();
+        let mut vm = Vm::new(&set, program);
+        assert_eq!(vm.run_to_end(), StopReason::InvalidJump(1));
+        assert_eq!(vm.register("a"), 1);
+        assert_eq!(vm.state().cycle, 2);
+        assert_eq!(vm.run_to_end(), StopReason::InvalidJump(1));
+
+        let program = set.parse(&format!("jmp {}", i64::MIN)).unwrap();
+        let mut vm = Vm::new(&set, program);
+        assert_eq!(vm.run_to_end(), StopReason::InvalidJump(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one cycle")]
+    fn test_zero_cycles() {
+        InstructionSet::new(&["a"]).instruction("nop", 0, 0, |_, _| {});
+    }
+}
@@ -46,6 +46,7 @@ pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -
     }
 }
 
+#[allow(dead_code)]
 #[derive(Debug)]
 pub enum Error {
     BrokenPipe,
@@ -173,7 +174,7 @@ pub mod child_commands {
             .split(" samples)")
             .next()?
             .split('(')
-            .last()?
+            .next_back()?
             .split('@')
             .next()?
             .trim();
@@ -238,6 +239,22 @@ pub mod child_commands {
             assert_eq!(res.part_2.unwrap(), "100ms");
         }
 
+        #[test]
+        fn parses_rendered_parts() {
+            let res = parse_exec_time(
+                &[
+                    "Part 1: 13140 (1.5µs @ 10000 samples)".into(),
+                    "Part 2: EHZFZHCZ ▼  (3µs @ 10000 samples)".into(),
+                    "#### #  # ####".into(),
+                    "".into(),
+                ],
+                day!(10),
+            );
+            assert_approx_eq!(res.total_nanos, 4500_f64);
+            assert_eq!(res.part_1.unwrap(), "1.5µs");
+            assert_eq!(res.part_2.unwrap(), "3µs");
+        }
+
         #[test]
         fn parses_missing_parts() {
             let res = parse_exec_time(
@@ -6,6 +6,7 @@ use std::process::Output;
 use std::time::{Duration, Instant};
 use std::{cmp, env, process};
 
+use crate::majcn::ocr;
 use crate::template::ANSI_BOLD;
 use crate::template::{aoc_cli, Day, ANSI_ITALIC, ANSI_RESET};
 
@@ -58,13 +59,8 @@ fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) ->
     print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
     let _ = stdout.flush();
 
-    let bench_iterations = cmp::min(
-        10000,
-        cmp::max(
-            Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10),
-            10,
-        ),
-    );
+    let bench_iterations =
+        (Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10)).clamp(10, 10000);
 
     let mut timers: Vec<Duration> = vec![];
 
@@ -105,7 +101,10 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
     match result {
         Some(result) => {
             if result.to_string().contains('\n') {
-                let str = format!("{part}: ▼ {duration_str}");
+                let str = match recognize_rendering(result) {
+                    Some(text) => format!("{part}: {ANSI_BOLD}{text}{ANSI_RESET} ▼ {duration_str}"),
+                    None => format!("{part}: ▼ {duration_str}"),
+                };
                 if is_intermediate_result {
                     print!("{str}");
                 } else {
@@ -134,6 +133,18 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
     }
 }
 
+/// Multi-line results that render block letters are also shown and submitted as text.
+fn recognize_rendering<T: Display>(result: &T) -> Option<String> {
+    let rendering = result.to_string();
+    if !rendering.contains('\n') {
+        return None;
+    }
+
+    ocr::recognize_str(&rendering)
+        .ok()
+        .filter(|x| !x.is_empty())
+}
+
 /// Parse the arguments passed to `solve` and try to submit one part of the solution if:
 ///  1. we are in `--release` mode.
 ///  2. aoc-cli is installed.
@@ -169,6 +180,8 @@ fn submit_result<T: Display>(
         process::exit(1);
     }
 
+    let answer = recognize_rendering(&result).unwrap_or_else(|| result.to_string());
+
     println!("Submitting result via aoc-cli...");
-    Some(aoc_cli::submit(day, part, &result.to_string()))
+    Some(aoc_cli::submit(day, part, &answer))
 }